@@ -1,7 +1,49 @@
 #!/bin/zsh
+# Usage: ./run.sh [-I DIR | FLAG ...] [SOURCE] [LIBRARY.c | LIBRARY.so ...]
+# Every module SOURCE imports, found next to it or in a -I directory, is
+# compiled separately and linked with it. Compiler flags such as -O2,
+# --int-width=64 or --bounds-check are passed to every compilation, so that
+# the modules agree with the program. Extra C sources and shared libraries
+# are linked too, so that the program can call the functions it declares
+# with EXTERN.
 
-./target/debug/microc
-mlir-opt a.mlir --convert-func-to-llvm | mlir-translate --mlir-to-llvmir -o a.ll
-llc a.ll -o a.s
-clang a.s util4mlir.so -o a
-./a
\ No newline at end of file
+set -e
+
+compiler_flags=()
+while [[ $1 == -* ]]; do
+    if [[ $1 == -I ]]; then
+        compiler_flags+=(-I $2)
+        shift 2
+    else
+        compiler_flags+=($1)
+        shift
+    fi
+done
+
+source_file=${1:-test.m}
+link_inputs=(${@:2})
+for input in $link_inputs; do
+    case $input in
+        *.c|*.so) ;;
+        *) echo "run.sh: cannot link \`$input\`: expected a .c or .so file" >&2; exit 1 ;;
+    esac
+done
+
+# Lower NAME.mlir to the assembly file NAME.s
+lower() {
+    mlir-opt $1.mlir --finalize-memref-to-llvm --convert-arith-to-llvm --convert-cf-to-llvm --convert-func-to-llvm --reconcile-unrealized-casts | mlir-translate --mlir-to-llvmir -o $1.ll
+    llc $1.ll -o $1.s
+}
+
+objects=()
+for module in $(./target/debug/microc $compiler_flags --list-modules $source_file); do
+    name=a.${module:t:r}
+    ./target/debug/microc $compiler_flags -o $name.mlir $module
+    lower $name
+    objects+=($name.s)
+done
+
+./target/debug/microc $compiler_flags $source_file
+lower a
+clang a.s $objects util4mlir.so $link_inputs -o a
+./a
@@ -0,0 +1,80 @@
+// Source position, 1-based; line 0 means unknown
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Loc {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Loc {
+    pub fn new(line: usize, col: usize) -> Self {
+        Loc { line, col }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level {
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub loc: Loc,
+    pub message: String,
+}
+
+pub struct Diag4m {
+    file: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diag4m {
+    pub fn new(file: String) -> Self {
+        Diag4m {
+            file,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub fn warning(&mut self, loc: Loc, message: String) {
+        self.diagnostics.push(Diagnostic {
+            level: Level::Warning,
+            loc,
+            message,
+        });
+    }
+
+    pub fn error(&mut self, loc: Loc, message: String) {
+        self.diagnostics.push(Diagnostic {
+            level: Level::Error,
+            loc,
+            message,
+        });
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.level == Level::Error)
+    }
+
+    // Print collected diagnostics to stderr in source order and clear them
+    pub fn flush(&mut self) {
+        self.diagnostics.sort_by_key(|diagnostic| diagnostic.loc);
+        for diagnostic in self.diagnostics.drain(..) {
+            let level = match diagnostic.level {
+                Level::Warning => "warning",
+                Level::Error => "error",
+            };
+            if diagnostic.loc.line == 0 {
+                eprintln!("{}: {}: {}", self.file, level, diagnostic.message);
+            } else {
+                eprintln!(
+                    "{}:{}:{}: {}: {}",
+                    self.file, diagnostic.loc.line, diagnostic.loc.col, level, diagnostic.message
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,671 @@
+use crate::diag4m::Loc;
+use crate::type4m::Ty;
+use std::collections::HashSet;
+use std::fmt;
+
+// Virtual register. Program variables and compiler temporaries are both
+// registers; variables may be assigned more than once, temporaries exactly once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Reg(pub usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlockId(pub usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    And,
+    Or,
+    Shl,
+    Shr,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    Xor,
+    Min,
+    Max,
+}
+
+impl BinOp {
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            BinOp::Add => "add",
+            BinOp::Sub => "sub",
+            BinOp::Mul => "mul",
+            BinOp::Div => "div",
+            BinOp::Rem => "rem",
+            BinOp::Pow => "pow",
+            BinOp::And => "and",
+            BinOp::Or => "or",
+            BinOp::Shl => "shl",
+            BinOp::Shr => "shr",
+            BinOp::Lt => "lt",
+            BinOp::Le => "le",
+            BinOp::Gt => "gt",
+            BinOp::Ge => "ge",
+            BinOp::Eq => "eq",
+            BinOp::Ne => "ne",
+            BinOp::Xor => "xor",
+            BinOp::Min => "min",
+            BinOp::Max => "max",
+        }
+    }
+
+    pub fn is_commutative(&self) -> bool {
+        matches!(
+            self,
+            BinOp::Add
+                | BinOp::Mul
+                | BinOp::And
+                | BinOp::Or
+                | BinOp::Eq
+                | BinOp::Ne
+                | BinOp::Xor
+                | BinOp::Min
+                | BinOp::Max
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Op {
+    Const {
+        dst: Reg,
+        value: i64,
+    },
+    ConstReal {
+        dst: Reg,
+        value: f64,
+    },
+    Copy {
+        dst: Reg,
+        src: Reg,
+    },
+    Binary {
+        dst: Reg,
+        op: BinOp,
+        lhs: Reg,
+        rhs: Reg,
+    },
+    // Convert `src` to the type of `dst`
+    Convert {
+        dst: Reg,
+        src: Reg,
+    },
+    Read {
+        dst: Reg,
+    },
+    Print {
+        src: Reg,
+    },
+    PrintStr {
+        text: String,
+    },
+    Call {
+        dst: Option<Reg>,
+        callee: String,
+        args: Vec<Reg>,
+    },
+    // Allocate the storage of the array or record variable `dst`
+    Alloca {
+        dst: Reg,
+    },
+    Load {
+        dst: Reg,
+        array: Reg,
+        index: Reg,
+    },
+    Store {
+        array: Reg,
+        index: Reg,
+        src: Reg,
+    },
+    // Fields are numbered in the order they are declared
+    LoadField {
+        dst: Reg,
+        record: Reg,
+        field: usize,
+    },
+    StoreField {
+        record: Reg,
+        field: usize,
+        src: Reg,
+    },
+    // Abort unless 0 <= index < len
+    CheckBounds {
+        index: Reg,
+        len: usize,
+    },
+    // Print `message` and exit with a failure status unless `cond` holds
+    Assert {
+        cond: Reg,
+        message: String,
+    },
+}
+
+impl Op {
+    pub fn def(&self) -> Option<Reg> {
+        match self {
+            Op::Const { dst, .. }
+            | Op::ConstReal { dst, .. }
+            | Op::Copy { dst, .. }
+            | Op::Binary { dst, .. }
+            | Op::Convert { dst, .. }
+            | Op::Read { dst }
+            | Op::Alloca { dst }
+            | Op::Load { dst, .. }
+            | Op::LoadField { dst, .. } => Some(*dst),
+            Op::Call { dst, .. } => *dst,
+            Op::Print { .. }
+            | Op::PrintStr { .. }
+            | Op::Store { .. }
+            | Op::StoreField { .. }
+            | Op::CheckBounds { .. }
+            | Op::Assert { .. } => None,
+        }
+    }
+
+    pub fn uses(&self) -> Vec<Reg> {
+        match self {
+            Op::Const { .. }
+            | Op::ConstReal { .. }
+            | Op::Read { .. }
+            | Op::PrintStr { .. }
+            | Op::Alloca { .. } => Vec::new(),
+            Op::Copy { src, .. }
+            | Op::Convert { src, .. }
+            | Op::Print { src }
+            | Op::LoadField { record: src, .. }
+            | Op::CheckBounds { index: src, .. }
+            | Op::Assert { cond: src, .. } => vec![*src],
+            Op::Binary { lhs, rhs, .. } => vec![*lhs, *rhs],
+            Op::Call { args, .. } => args.clone(),
+            Op::Load { array, index, .. } => vec![*array, *index],
+            Op::Store { array, index, src } => vec![*array, *index, *src],
+            Op::StoreField { record, src, .. } => vec![*record, *src],
+        }
+    }
+
+    // Whether the operation can be removed when its result is unused
+    pub fn is_pure(&self) -> bool {
+        match self {
+            Op::Const { .. }
+            | Op::ConstReal { .. }
+            | Op::Copy { .. }
+            | Op::Binary { .. }
+            | Op::Convert { .. }
+            | Op::Load { .. }
+            | Op::LoadField { .. } => true,
+            Op::Read { .. }
+            | Op::Print { .. }
+            | Op::PrintStr { .. }
+            | Op::Call { .. }
+            | Op::Alloca { .. }
+            | Op::Store { .. }
+            | Op::StoreField { .. }
+            | Op::CheckBounds { .. }
+            | Op::Assert { .. } => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Inst {
+    pub op: Op,
+    // Source statement the instruction was lowered from
+    pub loc: Loc,
+}
+
+impl Inst {
+    pub fn def(&self) -> Option<Reg> {
+        self.op.def()
+    }
+
+    pub fn uses(&self) -> Vec<Reg> {
+        self.op.uses()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Terminator {
+    Jump(BlockId),
+    Branch {
+        cond: Reg,
+        then_block: BlockId,
+        else_block: BlockId,
+    },
+    // Jump to the block of the case equal to `value`, or to `default`
+    Switch {
+        value: Reg,
+        default: BlockId,
+        cases: Vec<(i64, BlockId)>,
+    },
+    Return(Option<Reg>),
+}
+
+impl Terminator {
+    pub fn successors(&self) -> Vec<BlockId> {
+        match self {
+            Terminator::Jump(target) => vec![*target],
+            Terminator::Branch {
+                then_block,
+                else_block,
+                ..
+            } => vec![*then_block, *else_block],
+            Terminator::Switch { default, cases, .. } => std::iter::once(*default)
+                .chain(cases.iter().map(|(_, target)| *target))
+                .collect(),
+            Terminator::Return(_) => Vec::new(),
+        }
+    }
+
+    pub fn uses(&self) -> Vec<Reg> {
+        match self {
+            Terminator::Branch { cond, .. }
+            | Terminator::Switch { value: cond, .. }
+            | Terminator::Return(Some(cond)) => vec![*cond],
+            Terminator::Jump(_) | Terminator::Return(None) => Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Block {
+    pub insts: Vec<Inst>,
+    pub terminator: Terminator,
+}
+
+#[derive(Clone, Debug)]
+pub struct RegInfo {
+    // Source variable name, `None` for temporaries
+    pub name: Option<String>,
+    pub ty: Ty,
+}
+
+#[derive(Clone, Debug)]
+pub struct Function {
+    // Symbol name in the generated code
+    pub name: String,
+    // Not visible outside the object, for subprograms a MODULE does not export
+    pub private: bool,
+    pub params: Vec<Reg>,
+    pub ret: Option<Ty>,
+    pub regs: Vec<RegInfo>,
+    pub blocks: Vec<Block>,
+}
+
+impl Function {
+    pub fn new(name: String) -> Self {
+        Function {
+            name,
+            private: false,
+            params: Vec::new(),
+            ret: None,
+            regs: Vec::new(),
+            blocks: vec![Block {
+                insts: Vec::new(),
+                terminator: Terminator::Return(None),
+            }],
+        }
+    }
+
+    pub fn entry(&self) -> BlockId {
+        BlockId(0)
+    }
+
+    pub fn new_reg(&mut self, name: Option<String>, ty: Ty) -> Reg {
+        self.regs.push(RegInfo { name, ty });
+        Reg(self.regs.len() - 1)
+    }
+
+    pub fn reg_ty(&self, reg: Reg) -> Ty {
+        self.regs[reg.0].ty
+    }
+
+    pub fn new_block(&mut self) -> BlockId {
+        self.blocks.push(Block {
+            insts: Vec::new(),
+            terminator: Terminator::Return(None),
+        });
+        BlockId(self.blocks.len() - 1)
+    }
+
+    pub fn block(&self, id: BlockId) -> &Block {
+        &self.blocks[id.0]
+    }
+
+    pub fn block_mut(&mut self, id: BlockId) -> &mut Block {
+        &mut self.blocks[id.0]
+    }
+
+    pub fn block_ids(&self) -> impl DoubleEndedIterator<Item = BlockId> + use<> {
+        (0..self.blocks.len()).map(BlockId)
+    }
+
+    pub fn successors(&self, id: BlockId) -> Vec<BlockId> {
+        self.block(id).terminator.successors()
+    }
+
+    pub fn predecessors(&self) -> Vec<Vec<BlockId>> {
+        let mut preds = vec![Vec::new(); self.blocks.len()];
+        for id in self.block_ids() {
+            for succ in self.successors(id) {
+                if !preds[succ.0].contains(&id) {
+                    preds[succ.0].push(id);
+                }
+            }
+        }
+        preds
+    }
+
+    // Blocks in reverse post-order from the entry, unreachable blocks omitted
+    pub fn reverse_post_order(&self) -> Vec<BlockId> {
+        let mut visited = vec![false; self.blocks.len()];
+        let mut order = Vec::new();
+        let mut stack = vec![(self.entry(), 0)];
+        visited[self.entry().0] = true;
+
+        while let Some((id, next)) = stack.pop() {
+            let succs = self.successors(id);
+            if next < succs.len() {
+                stack.push((id, next + 1));
+                // Visit successors last to first so the first one comes first in the order
+                let succ = succs[succs.len() - 1 - next];
+                if !visited[succ.0] {
+                    visited[succ.0] = true;
+                    stack.push((succ, 0));
+                }
+            } else {
+                order.push(id);
+            }
+        }
+
+        order.reverse();
+        order
+    }
+
+    // Drop blocks that cannot be reached from the entry and renumber the rest
+    pub fn remove_unreachable_blocks(&mut self) -> bool {
+        let order = self.reverse_post_order();
+        if order.len() == self.blocks.len() {
+            return false;
+        }
+
+        let mut reachable = vec![false; self.blocks.len()];
+        for id in &order {
+            reachable[id.0] = true;
+        }
+        let mut new_ids = vec![BlockId(0); self.blocks.len()];
+        let mut next = 0;
+        for (i, reachable) in reachable.iter().enumerate() {
+            if *reachable {
+                new_ids[i] = BlockId(next);
+                next += 1;
+            }
+        }
+
+        let blocks = std::mem::take(&mut self.blocks);
+        for (i, mut block) in blocks.into_iter().enumerate() {
+            if !reachable[i] {
+                continue;
+            }
+            match &mut block.terminator {
+                Terminator::Jump(target) => *target = new_ids[target.0],
+                Terminator::Branch {
+                    then_block,
+                    else_block,
+                    ..
+                } => {
+                    *then_block = new_ids[then_block.0];
+                    *else_block = new_ids[else_block.0];
+                }
+                Terminator::Switch { default, cases, .. } => {
+                    *default = new_ids[default.0];
+                    for (_, target) in cases {
+                        *target = new_ids[target.0];
+                    }
+                }
+                Terminator::Return(_) => (),
+            }
+            self.blocks.push(block);
+        }
+        true
+    }
+
+    // Registers live on entry to each block, indexed by block id
+    pub fn live_in(&self) -> Vec<HashSet<Reg>> {
+        let mut uses = vec![HashSet::new(); self.blocks.len()];
+        let mut defs = vec![HashSet::new(); self.blocks.len()];
+        for id in self.block_ids() {
+            let block = self.block(id);
+            for inst in &block.insts {
+                for reg in inst.uses() {
+                    if !defs[id.0].contains(&reg) {
+                        uses[id.0].insert(reg);
+                    }
+                }
+                if let Some(reg) = inst.def() {
+                    defs[id.0].insert(reg);
+                }
+            }
+            for reg in block.terminator.uses() {
+                if !defs[id.0].contains(&reg) {
+                    uses[id.0].insert(reg);
+                }
+            }
+        }
+
+        let mut live_in = uses.clone();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for id in self.block_ids().rev() {
+                let mut live: HashSet<Reg> = uses[id.0].clone();
+                for succ in self.successors(id) {
+                    for reg in &live_in[succ.0] {
+                        if !defs[id.0].contains(reg) {
+                            live.insert(*reg);
+                        }
+                    }
+                }
+                if live.len() != live_in[id.0].len() {
+                    live_in[id.0] = live;
+                    changed = true;
+                }
+            }
+        }
+
+        live_in
+    }
+
+    pub fn reg_name(&self, reg: Reg) -> String {
+        match &self.regs[reg.0].name {
+            Some(name) => name.clone(),
+            None => format!("%{}", reg.0),
+        }
+    }
+
+    fn field_name(&self, record: Reg, field: usize) -> &str {
+        match self.reg_ty(record) {
+            Ty::Record(fields) => &fields.fields[field].0,
+            _ => panic!("Not a record"),
+        }
+    }
+
+    fn fmt_inst(&self, inst: &Inst) -> String {
+        match &inst.op {
+            Op::Const { dst, value } => format!("{} = {}", self.reg_name(*dst), value),
+            Op::ConstReal { dst, value } => format!("{} = {:?}", self.reg_name(*dst), value),
+            Op::Copy { dst, src } => {
+                format!("{} = {}", self.reg_name(*dst), self.reg_name(*src))
+            }
+            Op::Binary { dst, op, lhs, rhs } => format!(
+                "{} = {} {}, {}",
+                self.reg_name(*dst),
+                op.mnemonic(),
+                self.reg_name(*lhs),
+                self.reg_name(*rhs)
+            ),
+            Op::Convert { dst, src } => format!(
+                "{} = convert {} to {}",
+                self.reg_name(*dst),
+                self.reg_name(*src),
+                self.reg_ty(*dst)
+            ),
+            Op::Read { dst } => format!("{} = read", self.reg_name(*dst)),
+            Op::Print { src } => format!("print {}", self.reg_name(*src)),
+            Op::PrintStr { text } => format!("print {:?}", text),
+            Op::Call { dst, callee, args } => {
+                let args: Vec<String> = args.iter().map(|arg| self.reg_name(*arg)).collect();
+                match dst {
+                    Some(dst) => format!(
+                        "{} = call {}({})",
+                        self.reg_name(*dst),
+                        callee,
+                        args.join(", ")
+                    ),
+                    None => format!("call {}({})", callee, args.join(", ")),
+                }
+            }
+            Op::Alloca { dst } => format!("{} = alloca {}", self.reg_name(*dst), self.reg_ty(*dst)),
+            Op::Load { dst, array, index } => format!(
+                "{} = {}[{}]",
+                self.reg_name(*dst),
+                self.reg_name(*array),
+                self.reg_name(*index)
+            ),
+            Op::Store { array, index, src } => format!(
+                "{}[{}] = {}",
+                self.reg_name(*array),
+                self.reg_name(*index),
+                self.reg_name(*src)
+            ),
+            Op::LoadField { dst, record, field } => format!(
+                "{} = {}.{}",
+                self.reg_name(*dst),
+                self.reg_name(*record),
+                self.field_name(*record, *field)
+            ),
+            Op::StoreField { record, field, src } => format!(
+                "{}.{} = {}",
+                self.reg_name(*record),
+                self.field_name(*record, *field),
+                self.reg_name(*src)
+            ),
+            Op::CheckBounds { index, len } => {
+                format!("check_bounds {}, {}", self.reg_name(*index), len)
+            }
+            Op::Assert { cond, message } => {
+                format!("assert {}, {:?}", self.reg_name(*cond), message)
+            }
+        }
+    }
+
+    fn fmt_terminator(&self, terminator: &Terminator) -> String {
+        match terminator {
+            Terminator::Jump(target) => format!("jump bb{}", target.0),
+            Terminator::Branch {
+                cond,
+                then_block,
+                else_block,
+            } => format!(
+                "branch {}, bb{}, bb{}",
+                self.reg_name(*cond),
+                then_block.0,
+                else_block.0
+            ),
+            Terminator::Switch {
+                value,
+                default,
+                cases,
+            } => {
+                let cases: Vec<String> = cases
+                    .iter()
+                    .map(|(label, target)| format!("{}: bb{}", label, target.0))
+                    .collect();
+                format!(
+                    "switch {}, default bb{}, [{}]",
+                    self.reg_name(*value),
+                    default.0,
+                    cases.join(", ")
+                )
+            }
+            Terminator::Return(Some(value)) => format!("return {}", self.reg_name(*value)),
+            Terminator::Return(None) => "return".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params: Vec<String> = self.params.iter().map(|reg| self.reg_name(*reg)).collect();
+        writeln!(f, "function {}({})", self.name, params.join(", "))?;
+        let preds = self.predecessors();
+        for id in self.block_ids() {
+            let block = self.block(id);
+            if preds[id.0].is_empty() {
+                writeln!(f, "bb{}:", id.0)?;
+            } else {
+                let preds: Vec<String> = preds[id.0].iter().map(|p| format!("bb{}", p.0)).collect();
+                writeln!(f, "bb{}:  ; preds: {}", id.0, preds.join(", "))?;
+            }
+            for inst in &block.insts {
+                writeln!(f, "    {}", self.fmt_inst(inst))?;
+            }
+            writeln!(f, "    {}", self.fmt_terminator(&block.terminator))?;
+        }
+        Ok(())
+    }
+}
+
+// Function defined outside the program, declared with EXTERN
+#[derive(Clone, Debug)]
+pub struct Extern {
+    pub name: String,
+    pub params: Vec<Ty>,
+    // None for procedures
+    pub ret: Option<Ty>,
+}
+
+impl fmt::Display for Extern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params: Vec<String> = self.params.iter().map(Ty::to_string).collect();
+        write!(f, "extern {}({})", self.name, params.join(", "))?;
+        if let Some(ret) = self.ret {
+            write!(f, " : {}", ret)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Program {
+    pub externs: Vec<Extern>,
+    pub functions: Vec<Function>,
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for function in &self.externs {
+            writeln!(f, "{}", function)?;
+        }
+        if !self.externs.is_empty() {
+            writeln!(f)?;
+        }
+        for (i, function) in self.functions.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", function)?;
+        }
+        Ok(())
+    }
+}
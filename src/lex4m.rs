@@ -1,3 +1,4 @@
+use crate::diag4m::Loc;
 use lrlex::{lrlex_mod, LexerDef};
 use lrpar::{Lexeme, Lexer, NonStreamingLexer};
 
@@ -6,6 +7,7 @@ lrlex_mod!("micro.l");
 pub struct Lex4m {
     input: String,
     tokens: Vec<(String, String)>,
+    positions: Vec<Loc>,
 }
 
 impl Lex4m {
@@ -13,6 +15,7 @@ impl Lex4m {
         Lex4m {
             input,
             tokens: Vec::new(),
+            positions: Vec::new(),
         }
     }
 
@@ -20,6 +23,10 @@ impl Lex4m {
         &self.tokens
     }
 
+    pub fn positions(&self) -> &Vec<Loc> {
+        &self.positions
+    }
+
     pub fn lex(&mut self) {
         let lexerdef = micro_l::lexerdef();
         let lexer = lexerdef.lexer(&self.input);
@@ -30,7 +37,9 @@ impl Lex4m {
                     let span = lexer.span_str(lexeme.span());
                     let tok_id = lexeme.tok_id();
                     let tok_name = lexerdef.get_rule_by_id(tok_id).name().unwrap();
+                    let ((line, col), _) = lexer.line_col(lexeme.span());
                     self.tokens.push((tok_name.to_string(), span.to_string()));
+                    self.positions.push(Loc::new(line, col));
                 }
                 Err(err) => {
                     eprintln!("Error: {:?}", err);
@@ -39,6 +48,30 @@ impl Lex4m {
             }
         }
 
+        let eof = self.positions.last().copied().unwrap_or_default();
         self.tokens.push(("SCANEOF".to_string(), "".to_string()));
+        self.positions.push(eof);
+    }
+}
+
+// Decode the escapes of a string literal's text: \n, \t, \" and \\.
+// Returns the first unknown escape sequence on failure.
+pub fn unescape(text: &str) -> Result<String, String> {
+    let mut value = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some('"') => value.push('"'),
+            Some('\\') => value.push('\\'),
+            Some(other) => return Err(format!("\\{}", other)),
+            None => return Err("\\".to_string()),
+        }
     }
+    Ok(value)
 }
@@ -1,10 +1,18 @@
+mod diag4m;
+mod ir4m;
 mod lex4m;
 mod mlir4m;
+mod mod4m;
 mod node4m;
+mod opt4m;
 mod par4m;
+mod pass4m;
+mod sema4m;
+mod tac4m;
 mod token4m;
+mod type4m;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use lex4m::Lex4m;
 use std::path::PathBuf;
 
@@ -16,24 +24,93 @@ struct Args {
     #[arg(value_name = "INPUT", default_value = "test.m")]
     source_file: PathBuf,
 
-    /// Sets output LLVM IR file
-    #[arg(short, value_name = "OUTPUT", default_value = "a.ll")]
+    /// Sets the output file [default: a.mlir, or a.tac with --emit tac]
+    #[arg(short, value_name = "OUTPUT")]
     output_file: Option<PathBuf>,
 
-    /// Use verbose output
+    /// Adds a directory to search for imported modules
+    #[arg(short = 'I', value_name = "DIR")]
+    include_dirs: Vec<PathBuf>,
+
+    /// Prints the source file of every imported module, dependencies first,
+    /// and exits
+    #[arg(long)]
+    list_modules: bool,
+
+    /// Sets the kind of output to emit
+    #[arg(long, value_enum, default_value = "mlir")]
+    emit: Emit,
+
+    /// Sets the optimization level
+    #[arg(short = 'O', value_name = "LEVEL", default_value_t = 0,
+          value_parser = clap::value_parser!(u8).range(0..=2))]
+    opt_level: u8,
+
+    /// Runs the given passes instead of the -O pipeline
+    #[arg(long, value_name = "PASS", value_delimiter = ',')]
+    passes: Option<Vec<String>>,
+
+    /// Dumps the AST or TAC before the given passes ("all" for every pass)
+    #[arg(long, value_name = "PASS", value_delimiter = ',')]
+    print_before: Vec<String>,
+
+    /// Dumps the AST or TAC after the given passes ("all" for every pass)
+    #[arg(long, value_name = "PASS", value_delimiter = ',')]
+    print_after: Vec<String>,
+
+    /// Sets the width of INTEGER
+    #[arg(long, value_enum, value_name = "BITS", default_value = "32")]
+    int_width: IntWidth,
+
+    /// Requires every variable to be declared with DECLARE
+    #[arg(long)]
+    strict_declarations: bool,
+
+    /// Aborts at run time when an array index is out of bounds
+    #[arg(long)]
+    bounds_check: bool,
+
+    /// Drops ASSERT statements instead of checking them at run time
+    #[arg(long)]
+    no_asserts: bool,
+
+    /// Seeds RANDOM so that runs are reproducible
+    #[arg(long, value_name = "SEED")]
+    seed: Option<i64>,
+
+    /// Use verbose output (prints per-pass timing)
     #[arg(short, long, action = clap::ArgAction::Count)]
     verbose: u8,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Emit {
+    /// Three-address code dump, written to a.tac
+    Tac,
+    /// MLIR module, written to a.mlir
+    Mlir,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum IntWidth {
+    /// INTEGER is i32
+    #[value(name = "32")]
+    W32,
+    /// INTEGER is i64, the same as LONG
+    #[value(name = "64")]
+    W64,
+}
+
 fn main() {
     let args = Args::parse();
-    let input = std::fs::read_to_string(args.source_file).expect("Failed to read input file");
+    let input = std::fs::read_to_string(&args.source_file).expect("Failed to read input file");
+    let mut diag = diag4m::Diag4m::new(args.source_file.display().to_string());
 
     let mut lexer = Lex4m::new(input);
     lexer.lex();
     let tokens = lexer.tokens();
 
-    let tokens4m = token4m::Token4m::new(tokens.clone());
+    let tokens4m = token4m::Token4m::new(tokens.clone(), lexer.positions().clone());
     let mut parser = par4m::Par4m::new(tokens4m);
     parser.generate_concrete_syntax_tree();
     parser.generate_abstract_syntax_tree();
@@ -44,9 +121,86 @@ fn main() {
     std::fs::write("cst.dot", cst).expect("Unable to write file");
     std::fs::write("ast.dot", ast).expect("Unable to write file");
 
-    let mut mlir = mlir4m::Mlir4m::new(parser.abstract_syntax_tree());
+    for (loc, message) in parser.errors() {
+        diag.error(*loc, message.clone());
+    }
+    if diag.has_errors() {
+        diag.flush();
+        std::process::exit(1);
+    }
+
+    let mut ast = parser.abstract_syntax_tree().clone();
+    let int_ty = match args.int_width {
+        IntWidth::W32 => type4m::Ty::Integer,
+        IntWidth::W64 => type4m::Ty::Long,
+    };
+    let mut modules = mod4m::Mod4m::new(args.include_dirs, args.strict_declarations, int_ty);
+    modules.resolve(&mut ast, &args.source_file, &mut diag);
+    if args.list_modules {
+        let failed = diag.has_errors();
+        diag.flush();
+        if failed {
+            std::process::exit(1);
+        }
+        for source in modules.sources() {
+            println!("{}", source.display());
+        }
+        return;
+    }
+
+    let mut sema = sema4m::Sema4m::new(&mut diag);
+    sema.check(&ast);
+    let mut type_checker = type4m::Type4m::new(&mut diag, args.strict_declarations, int_ty);
+    type_checker.check(&mut ast);
+    if diag.has_errors() {
+        diag.flush();
+        std::process::exit(1);
+    }
+
+    let passes = args
+        .passes
+        .unwrap_or_else(|| pass4m::pipeline(args.opt_level));
+    let mut pass_manager = pass4m::PassManager::new(&passes).unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    });
+    let printed = pass_manager
+        .set_print_before(args.print_before)
+        .and_then(|()| pass_manager.set_print_after(args.print_after));
+    if let Err(err) = printed {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+    pass_manager.set_verbose(args.verbose > 0);
+
+    pass_manager.run_ast(&mut ast);
+
+    let mut tac = tac4m::Tac4m::new(&ast);
+    tac.set_bounds_check(args.bounds_check);
+    if !args.no_asserts {
+        tac.set_asserts(Some(args.source_file.display().to_string()));
+    }
+    tac.set_seed(args.seed);
+    let mut program = tac.generate_tac();
+    for function in &program.functions {
+        opt4m::dead_store_warnings(function, &mut diag);
+    }
+    diag.flush();
+
+    pass_manager.run_tac(&mut program);
+
+    match args.emit {
+        Emit::Tac => {
+            let output_file = args.output_file.unwrap_or(PathBuf::from("a.tac"));
+            std::fs::write(output_file, program.to_string()).expect("Unable to write file");
+        }
+        Emit::Mlir => {
+            let mut mlir = mlir4m::Mlir4m::new(&program);
 
-    let mlir_str = mlir.generate_mlir();
-    // println!("{}", mlir_str);
-    std::fs::write("a.mlir", mlir_str).expect("Unable to write file");
+            let mlir_str = mlir.generate_mlir();
+            // println!("{}", mlir_str);
+            let output_file = args.output_file.unwrap_or(PathBuf::from("a.mlir"));
+            std::fs::write(output_file, mlir_str).expect("Unable to write file");
+        }
+    }
 }
@@ -1,31 +1,54 @@
-use crate::node4m::Node;
+use crate::ir4m::{BinOp, BlockId, Function, Inst, Op, Program, Reg, Terminator};
+use crate::type4m::Ty;
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicUsize, Ordering};
 
-static GLOBAL_COUNTER: AtomicUsize = AtomicUsize::new(0);
+// Entry points of the runtime library and the C library
+const RUNTIME: &[(&str, &str)] = &[
+    ("read", "() -> i32"),
+    ("read_long", "() -> i64"),
+    ("readreal", "() -> f64"),
+    ("print", "(i32)"),
+    ("print_long", "(i64)"),
+    ("writereal", "(f64)"),
+    ("print_bool", "(i1)"),
+    ("power", "(i32, i32) -> i32"),
+    ("power_long", "(i64, i64) -> i64"),
+    ("print_str", "(!llvm.ptr)"),
+    ("check_bounds", "(i64, i64, i32)"),
+    ("exit", "(i32)"),
+    ("check_assert", "(i1, !llvm.ptr)"),
+    ("isqrt", "(i32) -> i32"),
+    ("isqrt_long", "(i64) -> i64"),
+    ("random_int", "(i32) -> i32"),
+    ("random_long", "(i64) -> i64"),
+    ("random_seed", "(i64)"),
+];
 
-pub fn increment_global_counter() -> usize {
-    GLOBAL_COUNTER.fetch_add(1, Ordering::SeqCst) + 1
+// Whether `name` is declared by every module, so that an EXTERN of the same
+// name would clash with it
+pub fn is_runtime(name: &str) -> bool {
+    name == "main" || RUNTIME.iter().any(|(runtime, _)| *runtime == name)
 }
 
-pub fn get_global_counter() -> usize {
-    GLOBAL_COUNTER.load(Ordering::SeqCst)
-}
-
-pub fn reset_global_counter() {
-    GLOBAL_COUNTER.store(0, Ordering::SeqCst);
-}
 pub struct Mlir4m<'a> {
-    ast: &'a Node,
-    id_map: HashMap<String, usize>,
+    program: &'a Program,
+    // Next temporary number and next version of each variable, per function
+    temp_counter: usize,
+    versions: HashMap<String, usize>,
+    // SSA value currently holding each register, valid within one block
+    ssa_map: HashMap<Reg, String>,
+    // Global symbol of each string literal
+    strings: HashMap<String, String>,
 }
 
 impl<'a> Mlir4m<'a> {
-    pub fn new(ast: &'a Node) -> Self {
-        reset_global_counter();
+    pub fn new(program: &'a Program) -> Self {
         Self {
-            ast,
-            id_map: HashMap::new(),
+            program,
+            temp_counter: 0,
+            versions: HashMap::new(),
+            ssa_map: HashMap::new(),
+            strings: HashMap::new(),
         }
     }
 
@@ -34,163 +57,641 @@ impl<'a> Mlir4m<'a> {
         // Print module header
         mlir.push_str("module {\n");
 
-        mlir.push_str("  func.func private @read() -> i32\n");
-        mlir.push_str("  func.func private @print(i32)\n\n");
+        for (name, signature) in RUNTIME {
+            mlir.push_str(&format!("  func.func private @{}{}\n", name, signature));
+        }
+        for function in &self.program.externs {
+            mlir.push_str(&format!(
+                "  func.func private @{}{}\n",
+                function.name,
+                Self::signature(&function.params, function.ret)
+            ));
+        }
 
-        mlir.push_str("  func.func @main() {\n");
+        self.generate_strings(&mut mlir);
 
-        // Generate MLIR from AST
-        self.traverse_ast(self.ast, 4, &mut mlir);
+        for function in &self.program.functions {
+            mlir.push('\n');
+            self.generate_function(function, &mut mlir);
+        }
 
-        // Print function and module closing
-        mlir.push_str("    return\n");
-        mlir.push_str("  }\n");
+        // Print module closing
         mlir.push_str("}\n");
 
         mlir
     }
 
-    fn get_ssa(&mut self, node: &Node, increment: bool) -> (bool, usize) {
-        let value = if node.name() == "PLUSOP" || node.name() == "MINUSOP" {
-            node.id().to_string()
-        } else {
-            node.value().to_string()
-        };
+    // Every distinct string literal and assertion message becomes a
+    // NUL-terminated global constant
+    fn generate_strings(&mut self, mlir: &mut String) {
+        let program = self.program;
+        for function in &program.functions {
+            for block in &function.blocks {
+                for inst in &block.insts {
+                    let text = match &inst.op {
+                        Op::PrintStr { text } | Op::Assert { message: text, .. } => text,
+                        _ => continue,
+                    };
+                    if !self.strings.contains_key(text) {
+                        let symbol = format!("str{}", self.strings.len());
+                        mlir.push_str(&format!(
+                            "  llvm.mlir.global internal constant @{}(\"{}\\00\") : !llvm.array<{} x i8>\n",
+                            symbol,
+                            Self::string_literal(text),
+                            text.len() + 1
+                        ));
+                        self.strings.insert(text.clone(), symbol);
+                    }
+                }
+            }
+        }
+    }
+
+    // `(T, ...) -> R`, without the result for procedures
+    fn signature(params: &[Ty], ret: Option<Ty>) -> String {
+        let params: Vec<String> = params.iter().map(Ty::mlir).collect();
+        match ret {
+            Some(ret) => format!("({}) -> {}", params.join(", "), ret.mlir()),
+            None => format!("({})", params.join(", ")),
+        }
+    }
 
-        let id = self.id_map.get(&value);
-        match id {
-            Some(id) => {
-                if increment {
-                    let new_id = get_global_counter();
-                    increment_global_counter();
-                    self.id_map.insert(value.to_owned(), new_id);
+    // MLIR string attributes escape quotes, backslashes and non-printable
+    // bytes as two hex digits
+    fn string_literal(text: &str) -> String {
+        let mut literal = String::new();
+        for byte in text.bytes() {
+            if byte.is_ascii_graphic() && byte != b'"' && byte != b'\\' || byte == b' ' {
+                literal.push(byte as char);
+            } else {
+                literal.push_str(&format!("\\{:02X}", byte));
+            }
+        }
+        literal
+    }
+
+    // Fresh SSA name for a new value of `reg`: `%x_N` for the N-th value of
+    // variable `x`, `%tmpN` for temporaries
+    fn new_ssa(&mut self, function: &Function, reg: Reg) -> String {
+        match &function.regs[reg.0].name {
+            Some(name) => {
+                let version = self.versions.entry(name.clone()).or_insert(0);
+                let ssa = format!("%{}_{}", name, version);
+                *version += 1;
+                ssa
+            }
+            None => self.new_temp(),
+        }
+    }
+
+    // MLIR float literals need a decimal point, e.g. `1.0e-3` rather than `1e-3`
+    fn float_literal(value: f64) -> String {
+        let literal = format!("{:e}", value);
+        match literal.split_once('e') {
+            Some((mantissa, exponent)) if !mantissa.contains('.') => {
+                format!("{}.0e{}", mantissa, exponent)
+            }
+            _ => literal,
+        }
+    }
+
+    fn ssa(&self, reg: Reg) -> &str {
+        &self.ssa_map[&reg]
+    }
+
+    fn generate_function(&mut self, function: &Function, mlir: &mut String) {
+        self.temp_counter = 0;
+        self.versions.clear();
+        let live_in = function.live_in();
+        let mut live_in: Vec<Vec<Reg>> = live_in
+            .into_iter()
+            .map(|regs| {
+                let mut regs: Vec<Reg> = regs.into_iter().collect();
+                regs.sort();
+                regs
+            })
+            .collect();
 
-                    (false, new_id)
+        // Parameters are the entry block's arguments
+        self.ssa_map.clear();
+        let mut params = Vec::new();
+        for reg in &function.params {
+            let ssa = self.new_ssa(function, *reg);
+            params.push(format!("{}: {}", ssa, function.reg_ty(*reg).mlir()));
+            self.ssa_map.insert(*reg, ssa);
+        }
+        let visibility = if function.private { "private " } else { "" };
+        match function.ret {
+            Some(ty) => mlir.push_str(&format!(
+                "  func.func {}@{}({}) -> {} {{\n",
+                visibility,
+                function.name,
+                params.join(", "),
+                ty.mlir()
+            )),
+            None => mlir.push_str(&format!(
+                "  func.func {}@{}({}) {{\n",
+                visibility,
+                function.name,
+                params.join(", ")
+            )),
+        }
+
+        // Variables read before any assignment start out as zero
+        let entry = function.entry();
+        for reg in std::mem::take(&mut live_in[entry.0]) {
+            if function.params.contains(&reg) {
+                continue;
+            }
+            let ssa = self.new_ssa(function, reg);
+            let ty = function.reg_ty(reg);
+            let zero = match ty {
+                Ty::Real => Self::float_literal(0.0),
+                _ => "0".to_string(),
+            };
+            mlir.push_str(&format!(
+                "    {} = arith.constant {} : {} // Declare {}\n",
+                ssa,
+                zero,
+                ty.mlir(),
+                function.reg_name(reg)
+            ));
+            self.ssa_map.insert(reg, ssa);
+        }
+
+        for id in function.reverse_post_order() {
+            if id != entry {
+                self.ssa_map.clear();
+                let mut args = Vec::new();
+                for reg in &live_in[id.0] {
+                    let ssa = self.new_ssa(function, *reg);
+                    args.push(format!("{}: {}", ssa, function.reg_ty(*reg).mlir()));
+                    self.ssa_map.insert(*reg, ssa);
+                }
+                if args.is_empty() {
+                    mlir.push_str(&format!("  ^bb{}:\n", id.0));
                 } else {
-                    (false, *id)
+                    mlir.push_str(&format!("  ^bb{}({}):\n", id.0, args.join(", ")));
                 }
             }
-            None => {
-                let new_id = get_global_counter();
-                increment_global_counter();
-                self.id_map.insert(value.to_owned(), new_id);
-                (true, new_id)
+
+            let block = function.block(id);
+            for inst in &block.insts {
+                self.generate_inst(function, inst, mlir);
             }
+            self.generate_terminator(function, &block.terminator, &live_in, mlir);
         }
+
+        mlir.push_str("  }\n");
     }
 
-    fn traverse_ast(&mut self, node: &Node, indent: usize, mlir: &mut String) {
-        let spaces = " ".repeat(indent);
+    fn generate_inst(&mut self, function: &Function, inst: &Inst, mlir: &mut String) {
+        let spaces = " ".repeat(4);
 
-        match node.name().as_str() {
-            "READ" => {
-                for child in node.children() {
-                    let (flag, ssa) = self.get_ssa(child, false);
-                    if flag {
+        match &inst.op {
+            Op::Const { dst, value } => {
+                let ssa = self.new_ssa(function, *dst);
+                mlir.push_str(&format!(
+                    "{}{} = arith.constant {} : {}\n",
+                    spaces,
+                    ssa,
+                    value,
+                    function.reg_ty(*dst).mlir()
+                ));
+                self.ssa_map.insert(*dst, ssa);
+            }
+            Op::ConstReal { dst, value } => {
+                let ssa = self.new_ssa(function, *dst);
+                mlir.push_str(&format!(
+                    "{}{} = arith.constant {} : {}\n",
+                    spaces,
+                    ssa,
+                    Self::float_literal(*value),
+                    function.reg_ty(*dst).mlir()
+                ));
+                self.ssa_map.insert(*dst, ssa);
+            }
+            Op::Copy { dst, src } => {
+                let ssa = self.ssa(*src).to_string();
+                self.ssa_map.insert(*dst, ssa);
+            }
+            // Integer powers are computed by the runtime
+            Op::Binary {
+                dst,
+                op: BinOp::Pow,
+                lhs,
+                rhs,
+            } => {
+                let ty = function.reg_ty(*dst);
+                let callee = if ty == Ty::Long {
+                    "power_long"
+                } else {
+                    "power"
+                };
+                let ssa = self.new_ssa(function, *dst);
+                mlir.push_str(&format!(
+                    "{}{} = call @{}({}, {}) : ({}, {}) -> {}\n",
+                    spaces,
+                    ssa,
+                    callee,
+                    self.ssa(*lhs),
+                    self.ssa(*rhs),
+                    ty.mlir(),
+                    ty.mlir(),
+                    ty.mlir()
+                ));
+                self.ssa_map.insert(*dst, ssa);
+            }
+            Op::Binary { dst, op, lhs, rhs } => {
+                let opcode = if function.reg_ty(*lhs) == Ty::Real {
+                    match op {
+                        BinOp::Add => "arith.addf",
+                        BinOp::Sub => "arith.subf",
+                        BinOp::Mul => "arith.mulf",
+                        BinOp::Div => "arith.divf",
+                        BinOp::Lt => "arith.cmpf olt,",
+                        BinOp::Le => "arith.cmpf ole,",
+                        BinOp::Gt => "arith.cmpf ogt,",
+                        BinOp::Ge => "arith.cmpf oge,",
+                        BinOp::Eq => "arith.cmpf oeq,",
+                        BinOp::Ne => "arith.cmpf une,",
+                        BinOp::Min => "arith.minimumf",
+                        BinOp::Max => "arith.maximumf",
+                        BinOp::Rem
+                        | BinOp::Pow
+                        | BinOp::And
+                        | BinOp::Or
+                        | BinOp::Xor
+                        | BinOp::Shl
+                        | BinOp::Shr => {
+                            panic!("Unsupported REAL operation: {}", op.mnemonic())
+                        }
+                    }
+                } else {
+                    match op {
+                        BinOp::Add => "arith.addi",
+                        BinOp::Sub => "arith.subi",
+                        BinOp::Mul => "arith.muli",
+                        BinOp::Div => "arith.divsi",
+                        BinOp::Rem => "arith.remsi",
+                        BinOp::And => "arith.andi",
+                        BinOp::Or => "arith.ori",
+                        BinOp::Shl => "arith.shli",
+                        BinOp::Shr => "arith.shrsi",
+                        BinOp::Pow => unreachable!(),
+                        BinOp::Lt => "arith.cmpi slt,",
+                        BinOp::Le => "arith.cmpi sle,",
+                        BinOp::Gt => "arith.cmpi sgt,",
+                        BinOp::Ge => "arith.cmpi sge,",
+                        BinOp::Eq => "arith.cmpi eq,",
+                        BinOp::Ne => "arith.cmpi ne,",
+                        BinOp::Xor => "arith.xori",
+                        BinOp::Min => "arith.minsi",
+                        BinOp::Max => "arith.maxsi",
+                    }
+                };
+                let ssa = self.new_ssa(function, *dst);
+                mlir.push_str(&format!(
+                    "{}{} = {} {}, {} : {}\n",
+                    spaces,
+                    ssa,
+                    opcode,
+                    self.ssa(*lhs),
+                    self.ssa(*rhs),
+                    function.reg_ty(*lhs).mlir()
+                ));
+                self.ssa_map.insert(*dst, ssa);
+            }
+            Op::Convert { dst, src } => {
+                let from = function.reg_ty(*src);
+                let to = function.reg_ty(*dst);
+                let opcode = match (from, to) {
+                    (Ty::Integer, Ty::Long) => "arith.extsi",
+                    (Ty::Long, Ty::Integer) => "arith.trunci",
+                    (Ty::Integer | Ty::Long, Ty::Real) => "arith.sitofp",
+                    _ => panic!("Unsupported conversion from {} to {}", from, to),
+                };
+                let ssa = self.new_ssa(function, *dst);
+                mlir.push_str(&format!(
+                    "{}{} = {} {} : {} to {}\n",
+                    spaces,
+                    ssa,
+                    opcode,
+                    self.ssa(*src),
+                    from.mlir(),
+                    to.mlir()
+                ));
+                self.ssa_map.insert(*dst, ssa);
+            }
+            Op::Read { dst } => {
+                let ty = function.reg_ty(*dst);
+                let callee = match ty {
+                    Ty::Long => "read_long",
+                    Ty::Real => "readreal",
+                    _ => "read",
+                };
+                let ssa = self.new_ssa(function, *dst);
+                mlir.push_str(&format!(
+                    "{}{} = call @{}() : () -> {}\n",
+                    spaces,
+                    ssa,
+                    callee,
+                    ty.mlir()
+                ));
+                self.ssa_map.insert(*dst, ssa);
+            }
+            Op::Print { src } => {
+                let ty = function.reg_ty(*src);
+                let callee = match ty {
+                    Ty::Integer => "print",
+                    Ty::Long => "print_long",
+                    Ty::Real => "writereal",
+                    Ty::Boolean => "print_bool",
+                    Ty::Array(..) => panic!("Cannot print an array"),
+                    Ty::Record(_) => panic!("Cannot print a record"),
+                };
+                mlir.push_str(&format!(
+                    "{}call @{}({}) : ({}) -> ()\n",
+                    spaces,
+                    callee,
+                    self.ssa(*src),
+                    ty.mlir()
+                ));
+            }
+            Op::PrintStr { text } => {
+                let ptr = self.new_temp();
+                mlir.push_str(&format!(
+                    "{}{} = llvm.mlir.addressof @{} : !llvm.ptr\n",
+                    spaces, ptr, self.strings[text]
+                ));
+                mlir.push_str(&format!(
+                    "{}call @print_str({}) : (!llvm.ptr) -> ()\n",
+                    spaces, ptr
+                ));
+            }
+            Op::Assert { cond, message } => {
+                let ptr = self.new_temp();
+                mlir.push_str(&format!(
+                    "{}{} = llvm.mlir.addressof @{} : !llvm.ptr\n",
+                    spaces, ptr, self.strings[message]
+                ));
+                mlir.push_str(&format!(
+                    "{}call @check_assert({}, {}) : (i1, !llvm.ptr) -> ()\n",
+                    spaces,
+                    self.ssa(*cond),
+                    ptr
+                ));
+            }
+            Op::Call { dst, callee, args } => {
+                let values: Vec<&str> = args.iter().map(|reg| self.ssa(*reg)).collect();
+                let types: Vec<String> = args
+                    .iter()
+                    .map(|reg| function.reg_ty(*reg).mlir())
+                    .collect();
+                let call = format!(
+                    "call @{}({}) : ({})",
+                    callee,
+                    values.join(", "),
+                    types.join(", ")
+                );
+                match dst {
+                    Some(dst) => {
+                        let ssa = self.new_ssa(function, *dst);
                         mlir.push_str(&format!(
-                            "{}%{} = arith.constant 0 : i32 // Declare {}\n",
+                            "{}{} = {} -> {}\n",
                             spaces,
                             ssa,
-                            child.value()
+                            call,
+                            function.reg_ty(*dst).mlir()
                         ));
+                        self.ssa_map.insert(*dst, ssa);
                     }
-
-                    mlir.push_str(&format!(
-                        "{}%{} = call @read() : () -> i32\n",
-                        spaces,
-                        self.get_ssa(child, true).1
-                    ));
+                    None => mlir.push_str(&format!("{}{} -> ()\n", spaces, call)),
                 }
-                mlir.push('\n');
-            }
-            "WRITE" => {
-                for child in node.children() {
-                    match child.name().as_str() {
-                        "INTLITERAL" => {
-                            mlir.push_str(&format!(
-                                "{}%{} = arith.constant {} : i32\n",
-                                spaces,
-                                self.get_ssa(child, true).1,
-                                child.value()
-                            ));
-                            mlir.push_str(&format!(
-                                "{}call @print(%{}) : (i32) -> ()\n",
-                                spaces,
-                                self.get_ssa(child, false).1,
-                            ))
-                        }
-                        "ID" => mlir.push_str(&format!(
-                            "{}call @print(%{}) : (i32) -> ()\n",
+            }
+            Op::Alloca { dst } => {
+                let ssa = self.new_ssa(function, *dst);
+                match function.reg_ty(*dst) {
+                    Ty::Record(record) => {
+                        let one = self.new_temp();
+                        mlir.push_str(&format!(
+                            "{}{} = llvm.mlir.constant(1 : i64) : i64\n",
+                            spaces, one
+                        ));
+                        mlir.push_str(&format!(
+                            "{}{} = llvm.alloca {} x {} : (i64) -> !llvm.ptr\n",
                             spaces,
-                            self.get_ssa(child, false).1
-                        )),
-                        _ => {
-                            self.traverse_ast(child, indent, mlir);
-                            mlir.push_str(&format!(
-                                "{}call @print(%{}) : (i32) -> ()\n",
-                                spaces,
-                                self.get_ssa(child, false).1
-                            ))
-                        }
+                            ssa,
+                            one,
+                            record.mlir_struct()
+                        ));
                     }
+                    ty => mlir.push_str(&format!(
+                        "{}{} = memref.alloca() : {}\n",
+                        spaces,
+                        ssa,
+                        ty.mlir()
+                    )),
                 }
+                self.ssa_map.insert(*dst, ssa);
             }
-            "INTLITERAL" => {
+            Op::Load { dst, array, index } => {
+                let index = self.index_cast(function, *index, mlir);
+                let ssa = self.new_ssa(function, *dst);
                 mlir.push_str(&format!(
-                    "{}%{} = arith.constant {} : i32\n",
+                    "{}{} = memref.load {}[{}] : {}\n",
                     spaces,
-                    self.get_ssa(node, true).1,
-                    node.value()
+                    ssa,
+                    self.ssa(*array),
+                    index,
+                    function.reg_ty(*array).mlir()
                 ));
+                self.ssa_map.insert(*dst, ssa);
             }
-            "PLUSOP" => {
-                self.traverse_ast(&node.children()[0], indent, mlir);
-                self.traverse_ast(&node.children()[1], indent, mlir);
-
+            Op::Store { array, index, src } => {
+                let index = self.index_cast(function, *index, mlir);
                 mlir.push_str(&format!(
-                    "{}%{} = arith.addi %{}, %{} : i32\n",
+                    "{}memref.store {}, {}[{}] : {}\n",
                     spaces,
-                    self.get_ssa(node, false).1,
-                    self.get_ssa(&node.children()[0], false).1,
-                    self.get_ssa(&node.children()[1], false).1,
+                    self.ssa(*src),
+                    self.ssa(*array),
+                    index,
+                    function.reg_ty(*array).mlir()
                 ));
             }
-            "MINUSOP" => {
-                self.traverse_ast(&node.children()[0], indent, mlir);
-                self.traverse_ast(&node.children()[1], indent, mlir);
-
+            Op::LoadField { dst, record, field } => {
+                let ptr = self.field_ptr(function, *record, *field, mlir);
+                let ssa = self.new_ssa(function, *dst);
+                mlir.push_str(&format!(
+                    "{}{} = llvm.load {} : !llvm.ptr -> {}\n",
+                    spaces,
+                    ssa,
+                    ptr,
+                    function.reg_ty(*dst).mlir()
+                ));
+                self.ssa_map.insert(*dst, ssa);
+            }
+            Op::StoreField { record, field, src } => {
+                let ptr = self.field_ptr(function, *record, *field, mlir);
                 mlir.push_str(&format!(
-                    "{}%{} = arith.subi %{}, %{} : i32\n",
+                    "{}llvm.store {}, {} : {}, !llvm.ptr\n",
                     spaces,
-                    self.get_ssa(node, false).1,
-                    self.get_ssa(&node.children()[0], false).1,
-                    self.get_ssa(&node.children()[1], false).1,
+                    self.ssa(*src),
+                    ptr,
+                    function.reg_ty(*src).mlir()
                 ));
             }
-            "ASSIGNOP" => {
-                self.traverse_ast(&node.children()[1], indent, mlir);
+            Op::CheckBounds { index, len } => {
+                let ty = function.reg_ty(*index);
+                let mut index = self.ssa(*index).to_string();
+                if ty == Ty::Integer {
+                    let wide = self.new_temp();
+                    mlir.push_str(&format!(
+                        "{}{} = arith.extsi {} : i32 to i64\n",
+                        spaces, wide, index
+                    ));
+                    index = wide;
+                }
+                let len_ssa = self.new_temp();
+                mlir.push_str(&format!(
+                    "{}{} = arith.constant {} : i64\n",
+                    spaces, len_ssa, len
+                ));
+                let line = self.new_temp();
                 mlir.push_str(&format!(
-                    "{}%{} = arith.constant 0 : i32 // Declare {}\n",
+                    "{}{} = arith.constant {} : i32\n",
+                    spaces, line, inst.loc.line
+                ));
+                mlir.push_str(&format!(
+                    "{}call @check_bounds({}, {}, {}) : (i64, i64, i32) -> ()\n",
+                    spaces, index, len_ssa, line
+                ));
+            }
+        }
+    }
+
+    fn new_temp(&mut self) -> String {
+        let ssa = format!("%tmp{}", self.temp_counter);
+        self.temp_counter += 1;
+        ssa
+    }
+
+    // memref subscripts have the `index` type
+    fn index_cast(&mut self, function: &Function, index: Reg, mlir: &mut String) -> String {
+        let ssa = self.new_temp();
+        mlir.push_str(&format!(
+            "    {} = arith.index_cast {} : {} to index\n",
+            ssa,
+            self.ssa(index),
+            function.reg_ty(index).mlir()
+        ));
+        ssa
+    }
+
+    // Address of a field of the record `record` points to
+    fn field_ptr(
+        &mut self,
+        function: &Function,
+        record: Reg,
+        field: usize,
+        mlir: &mut String,
+    ) -> String {
+        let Ty::Record(fields) = function.reg_ty(record) else {
+            panic!("Not a record");
+        };
+        let ptr = self.new_temp();
+        mlir.push_str(&format!(
+            "    {} = llvm.getelementptr {}[0, {}] : (!llvm.ptr) -> !llvm.ptr, {}\n",
+            ptr,
+            self.ssa(record),
+            field,
+            fields.mlir_struct()
+        ));
+        ptr
+    }
+
+    // Successor block with the current values of its live-in registers
+    fn successor(&self, function: &Function, target: BlockId, live_in: &[Vec<Reg>]) -> String {
+        let regs = &live_in[target.0];
+        if regs.is_empty() {
+            return format!("^bb{}", target.0);
+        }
+        let values: Vec<&str> = regs.iter().map(|reg| self.ssa(*reg)).collect();
+        let types: Vec<String> = regs
+            .iter()
+            .map(|reg| function.reg_ty(*reg).mlir())
+            .collect();
+        format!(
+            "^bb{}({} : {})",
+            target.0,
+            values.join(", "),
+            types.join(", ")
+        )
+    }
+
+    fn generate_terminator(
+        &mut self,
+        function: &Function,
+        terminator: &Terminator,
+        live_in: &[Vec<Reg>],
+        mlir: &mut String,
+    ) {
+        let spaces = " ".repeat(4);
+
+        match terminator {
+            Terminator::Jump(target) => {
+                mlir.push_str(&format!(
+                    "{}cf.br {}\n",
                     spaces,
-                    self.get_ssa(&node.children()[0], true).1,
-                    node.children()[0].value()
+                    self.successor(function, *target, live_in)
                 ));
-                let (flag, old_ssa) = self.get_ssa(&node.children()[0], false);
+            }
+            Terminator::Branch {
+                cond,
+                then_block,
+                else_block,
+            } => {
                 mlir.push_str(&format!(
-                    "{}%{} = arith.addi %{}, %{} : i32\n",
+                    "{}cf.cond_br {}, {}, {}\n",
                     spaces,
-                    self.get_ssa(&node.children()[0], true).1,
-                    old_ssa,
-                    self.get_ssa(&node.children()[1], false).1
+                    self.ssa(*cond),
+                    self.successor(function, *then_block, live_in),
+                    self.successor(function, *else_block, live_in)
                 ));
-
-                mlir.push('\n');
             }
-            _ => {
-                for child in node.children() {
-                    self.traverse_ast(child, indent, mlir);
+            Terminator::Switch {
+                value,
+                default,
+                cases,
+            } => {
+                mlir.push_str(&format!(
+                    "{}cf.switch {} : {}, [\n",
+                    spaces,
+                    self.ssa(*value),
+                    function.reg_ty(*value).mlir()
+                ));
+                mlir.push_str(&format!(
+                    "{}  default: {}",
+                    spaces,
+                    self.successor(function, *default, live_in)
+                ));
+                for (label, target) in cases {
+                    mlir.push_str(&format!(
+                        ",\n{}  {}: {}",
+                        spaces,
+                        label,
+                        self.successor(function, *target, live_in)
+                    ));
                 }
+                mlir.push_str(&format!("\n{}]\n", spaces));
             }
+            Terminator::Return(Some(value)) => mlir.push_str(&format!(
+                "{}return {} : {}\n",
+                spaces,
+                self.ssa(*value),
+                function.reg_ty(*value).mlir()
+            )),
+            Terminator::Return(None) => mlir.push_str(&format!("{}return\n", spaces)),
         }
     }
 }
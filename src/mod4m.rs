@@ -0,0 +1,177 @@
+use crate::diag4m::{Diag4m, Loc};
+use crate::lex4m::Lex4m;
+use crate::node4m::Node;
+use crate::par4m::Par4m;
+use crate::sema4m::Sema4m;
+use crate::token4m::Token4m;
+use crate::type4m::{Ty, Type4m};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// Resolves IMPORTs. An imported module is found next to the importing file
+// or in an include directory, checked on its own, and its exported types,
+// subprograms and constants replace the children of the IMPORT node.
+pub struct Mod4m {
+    include_dirs: Vec<PathBuf>,
+    strict: bool,
+    int_ty: Ty,
+    // Interface of every module checked so far, None if it has errors
+    interfaces: HashMap<String, Option<Node>>,
+    // Source file of every module checked so far, dependencies first
+    sources: Vec<PathBuf>,
+    // Modules being checked, innermost last, to detect circular imports
+    loading: Vec<String>,
+}
+
+impl Mod4m {
+    pub fn new(include_dirs: Vec<PathBuf>, strict: bool, int_ty: Ty) -> Self {
+        Mod4m {
+            include_dirs,
+            strict,
+            int_ty,
+            interfaces: HashMap::new(),
+            sources: Vec::new(),
+            loading: Vec::new(),
+        }
+    }
+
+    pub fn sources(&self) -> &[PathBuf] {
+        &self.sources
+    }
+
+    // Resolve the IMPORTs of `ast`, which was parsed from `source_file`
+    pub fn resolve(&mut self, ast: &mut Node, source_file: &Path, diag: &mut Diag4m) {
+        let dir = source_file.parent().unwrap_or(Path::new("."));
+        for child in ast.children_mut() {
+            if child.name() != "IMPORT" {
+                continue;
+            }
+            if let Some(mut interface) = self.load(child.value(), dir, child.loc(), diag) {
+                // Clashes with imported names are reported at the IMPORT
+                for item in interface.children_mut() {
+                    Self::relocate(item, child.loc());
+                }
+                *child.children_mut() = interface.take_children();
+            }
+        }
+    }
+
+    fn relocate(node: &mut Node, loc: Loc) {
+        node.set_loc(loc);
+        for child in node.children_mut() {
+            Self::relocate(child, loc);
+        }
+    }
+
+    // `name.m` in the importing file's directory, then in the include
+    // directories in the order they were given
+    fn find(&self, name: &str, dir: &Path) -> Option<PathBuf> {
+        let file = format!("{}.m", name);
+        std::iter::once(dir)
+            .chain(self.include_dirs.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(&file))
+            .find(|path| path.is_file())
+    }
+
+    fn load(&mut self, name: &str, dir: &Path, loc: Loc, diag: &mut Diag4m) -> Option<Node> {
+        if let Some(interface) = self.interfaces.get(name) {
+            return interface.clone();
+        }
+        if self.loading.iter().any(|loading| loading == name) {
+            diag.error(loc, format!("circular import of module `{}`", name));
+            return None;
+        }
+
+        let Some(path) = self.find(name, dir) else {
+            diag.error(loc, format!("cannot find module `{}`", name));
+            return None;
+        };
+        let input = match std::fs::read_to_string(&path) {
+            Ok(input) => input,
+            Err(err) => {
+                diag.error(loc, format!("cannot read `{}`: {}", path.display(), err));
+                return None;
+            }
+        };
+
+        // Errors in the module are reported against its own file; its
+        // warnings only when it is compiled by itself
+        let mut module_diag = Diag4m::new(path.display().to_string());
+        let mut ast = Self::parse(input, &mut module_diag);
+        match ast.children().first() {
+            Some(module) if module.name() == "MODULE" && module.value() == name => {}
+            _ => {
+                diag.error(
+                    loc,
+                    format!("`{}` does not declare MODULE {}", path.display(), name),
+                );
+                return None;
+            }
+        }
+
+        self.loading.push(name.to_string());
+        self.resolve(&mut ast, &path, &mut module_diag);
+        Sema4m::new(&mut module_diag).check(&ast);
+        Type4m::new(&mut module_diag, self.strict, self.int_ty).check(&mut ast);
+        self.loading.pop();
+
+        let interface = if module_diag.has_errors() {
+            module_diag.flush();
+            diag.error(loc, format!("module `{}` has errors", name));
+            None
+        } else {
+            self.sources.push(path);
+            Some(Self::interface(&ast))
+        };
+        self.interfaces.insert(name.to_string(), interface.clone());
+        interface
+    }
+
+    fn parse(input: String, diag: &mut Diag4m) -> Node {
+        let mut lexer = Lex4m::new(input);
+        lexer.lex();
+        let tokens = Token4m::new(lexer.tokens().clone(), lexer.positions().clone());
+        let mut parser = Par4m::new(tokens);
+        parser.generate_abstract_syntax_tree();
+        for (loc, message) in parser.errors() {
+            diag.error(*loc, message.clone());
+        }
+        parser.abstract_syntax_tree().clone()
+    }
+
+    // IMPORT node holding the exported types and subprograms, without their
+    // bodies, and a CONST with the exported constants, whose values the type
+    // checker has computed
+    fn interface(ast: &Node) -> Node {
+        let module = &ast.children()[0];
+        let exported = |name: &String| {
+            module
+                .children()
+                .iter()
+                .any(|export| export.value() == name)
+        };
+
+        let mut import_node = Node::new("IMPORT".to_string(), module.value().to_string());
+        let mut const_node = Node::new("CONST".to_string(), "const".to_string());
+        for child in ast.children() {
+            match child.name().as_str() {
+                "TYPEDEF" if exported(child.value()) => import_node.add_child(child.clone()),
+                "FUNCTION" | "PROCEDURE" if exported(child.value()) => {
+                    let mut header = child.clone();
+                    header.children_mut().truncate(1);
+                    import_node.add_child(header);
+                }
+                "CONST" => {
+                    for constant in child.children() {
+                        if exported(constant.value()) {
+                            const_node.add_child(constant.clone());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        import_node.add_child(const_node);
+        import_node
+    }
+}
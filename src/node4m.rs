@@ -1,3 +1,5 @@
+use crate::diag4m::Loc;
+use crate::type4m::Ty;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::{fmt, mem};
 
@@ -21,13 +23,12 @@ pub struct Node {
     value: String,
     children: Vec<Node>,
     id: usize,
+    loc: Loc,
+    // Type of an expression or variable, filled in by the type checker
+    ty: Option<Ty>,
 }
 
 impl Node {
-    pub fn id(&self) -> usize {
-        self.id
-    }
-
     pub fn set_name(&mut self, name: String) {
         self.name = name;
     }
@@ -36,6 +37,22 @@ impl Node {
         self.value = value;
     }
 
+    pub fn set_loc(&mut self, loc: Loc) {
+        self.loc = loc;
+    }
+
+    pub fn loc(&self) -> Loc {
+        self.loc
+    }
+
+    pub fn set_ty(&mut self, ty: Ty) {
+        self.ty = Some(ty);
+    }
+
+    pub fn ty(&self) -> Option<Ty> {
+        self.ty
+    }
+
     pub fn name(&self) -> &String {
         &self.name
     }
@@ -52,6 +69,8 @@ impl Node {
             value,
             children: Vec::new(),
             id,
+            loc: Loc::default(),
+            ty: None,
         }
     }
 
@@ -59,6 +78,10 @@ impl Node {
         &self.children
     }
 
+    pub fn children_mut(&mut self) -> &mut Vec<Node> {
+        &mut self.children
+    }
+
     pub fn take_children(&mut self) -> Vec<Node> {
         mem::take(&mut self.children)
     }
@@ -67,6 +90,38 @@ impl Node {
         self.children.push(child);
     }
 
+    // Clone of the subtree whose nodes get fresh ids
+    pub fn deep_copy(&self) -> Node {
+        let mut node = self.clone();
+        node.id = get_global_counter();
+        increment_global_counter();
+        node.children = self.children.iter().map(Node::deep_copy).collect();
+        node
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        self.to_text_helper(&mut text, 0);
+        text
+    }
+
+    fn to_text_helper(&self, text: &mut String, indent: usize) {
+        if self.name == self.value {
+            text.push_str(&format!("{}{}\n", " ".repeat(indent), self.name));
+        } else {
+            text.push_str(&format!(
+                "{}{} {}\n",
+                " ".repeat(indent),
+                self.name,
+                self.value
+            ));
+        }
+
+        for child in &self.children {
+            child.to_text_helper(text, indent + 2);
+        }
+    }
+
     pub fn to_dot(&self, display_value: bool) -> String {
         let mut dot = String::new();
         dot.push_str("digraph G {\n");
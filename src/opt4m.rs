@@ -0,0 +1,268 @@
+use crate::diag4m::{Diag4m, Loc};
+use crate::ir4m::{BinOp, Function, Inst, Op, Reg};
+use crate::node4m::Node;
+use crate::type4m::Ty;
+use std::collections::{HashMap, HashSet};
+
+// Fold additions and subtractions of integer literals in the AST
+pub fn fold(node: &mut Node) {
+    let children = node.take_children();
+    for mut child in children {
+        fold(&mut child);
+        node.add_child(child);
+    }
+
+    if node.name() != "PLUSOP" && node.name() != "MINUSOP" {
+        return;
+    }
+
+    let lhs = &node.children()[0];
+    let rhs = &node.children()[1];
+    if lhs.name() != "INTLITERAL" || rhs.name() != "INTLITERAL" {
+        return;
+    }
+
+    let (Ok(lhs), Ok(rhs)) = (lhs.value().parse::<i64>(), rhs.value().parse::<i64>()) else {
+        return;
+    };
+    let value = if node.name() == "PLUSOP" {
+        lhs.wrapping_add(rhs)
+    } else {
+        lhs.wrapping_sub(rhs)
+    };
+    let value = match node.ty() {
+        Some(Ty::Long) => value,
+        _ => value as i32 as i64,
+    };
+
+    node.take_children();
+    node.set_name("INTLITERAL".to_string());
+    node.set_value(value.to_string());
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum ValueKey {
+    Const(i64, Ty),
+    ConstReal(u64),
+    Binary(BinOp, usize, usize),
+    Convert(usize, Ty),
+}
+
+// Local value numbering: within each block, reuse the register that already
+// holds a constant or the result of an identical pure computation
+pub fn cse(function: &mut Function) {
+    for block in &mut function.blocks {
+        let mut next_vn = 0;
+        let mut reg_vn: HashMap<Reg, usize> = HashMap::new();
+        let mut key_vn: HashMap<ValueKey, usize> = HashMap::new();
+        let mut vn_holder: HashMap<usize, Reg> = HashMap::new();
+
+        let mut vn_of = |reg: Reg, reg_vn: &mut HashMap<Reg, usize>| {
+            *reg_vn.entry(reg).or_insert_with(|| {
+                next_vn += 1;
+                next_vn
+            })
+        };
+
+        for inst in &mut block.insts {
+            let (dst, key) = match inst.op {
+                Op::Const { dst, value } => (dst, ValueKey::Const(value, function.regs[dst.0].ty)),
+                Op::ConstReal { dst, value } => (dst, ValueKey::ConstReal(value.to_bits())),
+                Op::Binary { dst, op, lhs, rhs } => {
+                    let mut lhs = vn_of(lhs, &mut reg_vn);
+                    let mut rhs = vn_of(rhs, &mut reg_vn);
+                    if op.is_commutative() && lhs > rhs {
+                        std::mem::swap(&mut lhs, &mut rhs);
+                    }
+                    (dst, ValueKey::Binary(op, lhs, rhs))
+                }
+                Op::Convert { dst, src } => {
+                    let src = vn_of(src, &mut reg_vn);
+                    (dst, ValueKey::Convert(src, function.regs[dst.0].ty))
+                }
+                Op::Copy { dst, src } => {
+                    let vn = vn_of(src, &mut reg_vn);
+                    reg_vn.insert(dst, vn);
+                    continue;
+                }
+                _ => {
+                    if let Some(dst) = inst.def() {
+                        reg_vn.remove(&dst);
+                        vn_of(dst, &mut reg_vn);
+                    }
+                    continue;
+                }
+            };
+
+            if let Some(&vn) = key_vn.get(&key) {
+                let holder = vn_holder[&vn];
+                if reg_vn.get(&holder) == Some(&vn) {
+                    if holder != dst {
+                        inst.op = Op::Copy { dst, src: holder };
+                    }
+                    reg_vn.insert(dst, vn);
+                    continue;
+                }
+            }
+
+            reg_vn.remove(&dst);
+            let vn = vn_of(dst, &mut reg_vn);
+            key_vn.insert(key, vn);
+            vn_holder.insert(vn, dst);
+        }
+    }
+}
+
+// Remove unreachable blocks and side-effect free instructions whose
+// temporary result is never used
+pub fn dce(function: &mut Function) {
+    function.remove_unreachable_blocks();
+
+    loop {
+        let mut use_count: HashMap<Reg, usize> = HashMap::new();
+        for block in &function.blocks {
+            let terminator_uses = block.terminator.uses();
+            for reg in block
+                .insts
+                .iter()
+                .flat_map(Inst::uses)
+                .chain(terminator_uses)
+            {
+                *use_count.entry(reg).or_default() += 1;
+            }
+        }
+
+        let regs = &function.regs;
+        let mut changed = false;
+        for block in &mut function.blocks {
+            let before = block.insts.len();
+            block.insts.retain(|inst| match inst.def() {
+                Some(dst) if inst.op.is_pure() => {
+                    regs[dst.0].name.is_some() || use_count.contains_key(&dst)
+                }
+                _ => true,
+            });
+            changed |= block.insts.len() != before;
+        }
+
+        if !changed {
+            break;
+        }
+    }
+}
+
+// Registers live on exit from each block, indexed by block id
+fn live_out(function: &Function) -> Vec<HashSet<Reg>> {
+    let live_in = function.live_in();
+    function
+        .block_ids()
+        .map(|id| {
+            function
+                .successors(id)
+                .iter()
+                .flat_map(|succ| live_in[succ.0].iter().copied())
+                .collect()
+        })
+        .collect()
+}
+
+// Remove side-effect free instructions whose result is dead, including
+// stores to variables that are overwritten or never read afterwards
+pub fn dse(function: &mut Function) {
+    loop {
+        let live_out = live_out(function);
+        let mut changed = false;
+
+        for id in function.block_ids() {
+            let block = function.block_mut(id);
+            let mut live = live_out[id.0].clone();
+            live.extend(block.terminator.uses());
+
+            let mut keep = vec![true; block.insts.len()];
+            for (i, inst) in block.insts.iter().enumerate().rev() {
+                if let Some(dst) = inst.def() {
+                    if inst.op.is_pure() && !live.contains(&dst) {
+                        keep[i] = false;
+                        changed = true;
+                        continue;
+                    }
+                    live.remove(&dst);
+                }
+                live.extend(inst.uses());
+            }
+
+            let mut keep = keep.into_iter();
+            block.insts.retain(|_| keep.next().unwrap());
+        }
+
+        if !changed {
+            break;
+        }
+    }
+}
+
+// Warn about variables that are never used and about values stored into
+// variables that are overwritten or never read before the function ends
+pub fn dead_store_warnings(function: &Function, diag: &mut Diag4m) {
+    let mut used: HashSet<Reg> = HashSet::new();
+    let mut first_def: HashMap<Reg, Loc> = HashMap::new();
+    for block in &function.blocks {
+        used.extend(block.insts.iter().flat_map(Inst::uses));
+        used.extend(block.terminator.uses());
+        for inst in &block.insts {
+            if let Some(dst) = inst.def() {
+                let loc = first_def.entry(dst).or_insert(inst.loc);
+                *loc = (*loc).min(inst.loc);
+            }
+        }
+    }
+
+    let mut unused: Vec<(Loc, Reg)> = first_def
+        .iter()
+        .filter(|(reg, _)| function.regs[reg.0].name.is_some() && !used.contains(reg))
+        .map(|(reg, loc)| (*loc, *reg))
+        .collect();
+    unused.sort();
+    for (loc, reg) in unused {
+        diag.warning(
+            loc,
+            format!("variable `{}` is never used", function.reg_name(reg)),
+        );
+    }
+
+    let live_out = live_out(function);
+    for id in function.block_ids() {
+        let block = function.block(id);
+        let mut live = live_out[id.0].clone();
+        live.extend(block.terminator.uses());
+        // Variables assigned again later in this block before any use
+        let mut redefined: HashSet<Reg> = HashSet::new();
+
+        for inst in block.insts.iter().rev() {
+            if let Some(dst) = inst.def() {
+                let is_variable = function.regs[dst.0].name.is_some();
+                if is_variable && used.contains(&dst) && !live.contains(&dst) {
+                    let what = match inst.op {
+                        Op::Read { .. } => "value read into",
+                        _ => "value assigned to",
+                    };
+                    let why = if redefined.contains(&dst) {
+                        "is overwritten before being used"
+                    } else {
+                        "is never used"
+                    };
+                    diag.warning(
+                        inst.loc,
+                        format!("{} `{}` {}", what, function.reg_name(dst), why),
+                    );
+                }
+                live.remove(&dst);
+                redefined.insert(dst);
+            }
+            for reg in inst.uses() {
+                live.insert(reg);
+                redefined.remove(&reg);
+            }
+        }
+    }
+}
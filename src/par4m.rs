@@ -1,3 +1,4 @@
+use crate::diag4m::Loc;
 use crate::node4m::Node;
 use crate::token4m::Token4m;
 
@@ -5,6 +6,7 @@ pub struct Par4m {
     tokens: Token4m,
     concrete_syntax_tree: Node,
     abstract_syntax_tree: Node,
+    errors: Vec<(Loc, String)>,
 }
 
 impl Par4m {
@@ -16,24 +18,135 @@ impl Par4m {
         &self.abstract_syntax_tree
     }
 
+    // Syntax errors found while building the AST
+    pub fn errors(&self) -> &[(Loc, String)] {
+        &self.errors
+    }
+
     pub fn new(tokens: Token4m) -> Self {
         Par4m {
             tokens,
             concrete_syntax_tree: Node::new("ConcreteSyntaxTree".to_string(), "".to_string()),
             abstract_syntax_tree: Node::new("AbstractSyntaxTree".to_string(), "".to_string()),
+            errors: Vec::new(),
+        }
+    }
+
+    // Tokens that can begin a <statement>
+    fn _is_statement_start(token_type: &str) -> bool {
+        matches!(
+            token_type,
+            "ID" | "READ"
+                | "WRITE"
+                | "IF"
+                | "WHILE"
+                | "FOR"
+                | "REPEAT"
+                | "CASE"
+                | "RETURN"
+                | "BREAK"
+                | "CONTINUE"
+                | "EXIT"
+                | "ASSERT"
+        )
+    }
+
+    // Whether the ID at the current token starts an <index>
+    fn _is_index(&self) -> bool {
+        matches!(self.tokens.peek_token(1), Some((token_type, _)) if token_type == "LBRACKET")
+    }
+
+    // Whether the ID at the current token starts a <field>
+    fn _is_field(&self) -> bool {
+        matches!(self.tokens.peek_token(1), Some((token_type, _)) if token_type == "DOT")
+    }
+
+    // Whether the current token starts a <case_arm>, whose labels are
+    // followed by a COLON
+    fn _is_case_arm(&self) -> bool {
+        let mut offset = 0;
+        loop {
+            match self.tokens.peek_token(offset) {
+                Some((token_type, _)) if token_type == "INTLITERAL" || token_type == "ID" => {}
+                _ => return false,
+            }
+            match self.tokens.peek_token(offset + 1) {
+                Some((token_type, _)) if token_type == "COLON" => return true,
+                Some((token_type, _)) if token_type == "COMMA" => offset += 2,
+                _ => return false,
+            }
+        }
+    }
+
+    // Whether the ID at the current token starts a <call>
+    fn _is_call(&self) -> bool {
+        matches!(self.tokens.peek_token(1), Some((token_type, _)) if token_type == "LPAREN")
+    }
+
+    // <rel_op> ::= LTOP | LEOP | GTOP | GEOP | EQOP | NEOP
+    fn _is_rel_op(token_type: &str) -> bool {
+        matches!(
+            token_type,
+            "LTOP" | "LEOP" | "GTOP" | "GEOP" | "EQOP" | "NEOP"
+        )
+    }
+
+    // <compound_op> ::= PLUSASSIGN | MINUSASSIGN | MULTIASSIGN | DIVIDEASSIGN
+    // Each names the binary operator it applies
+    fn _compound_op(token_type: &str) -> Option<&'static str> {
+        match token_type {
+            "PLUSASSIGN" => Some("PLUSOP"),
+            "MINUSASSIGN" => Some("MINUSOP"),
+            "MULTIASSIGN" => Some("MULTIOP"),
+            "DIVIDEASSIGN" => Some("DIVIDEOP"),
+            _ => None,
         }
     }
 
+    // <add_op> ::= PLUSOP | MINUSOP | OR | XOR
+    fn _is_add_op(token_type: &str) -> bool {
+        matches!(token_type, "PLUSOP" | "MINUSOP" | "OR" | "XOR")
+    }
+
+    // <mul_op> ::= MULTIOP | DIVIDEOP | MOD | SHL | SHR | AND
+    fn _is_mul_op(token_type: &str) -> bool {
+        matches!(
+            token_type,
+            "MULTIOP" | "DIVIDEOP" | "MOD" | "SHL" | "SHR" | "AND"
+        )
+    }
+
+    // <type> ::= INTEGER | LONG | REAL | BOOLEAN
+    fn _is_type(token_type: &str) -> bool {
+        matches!(token_type, "INTEGER" | "LONG" | "REAL" | "BOOLEAN")
+    }
+
     /*
     AST
      */
 
+    // Syntax error at the current token. The parser does not recover, so
+    // only the first one is kept
+    fn _error(&mut self, message: String) {
+        if self.errors.is_empty() {
+            self.errors.push((self.tokens.position(), message));
+        }
+    }
+
+    // New AST node located at the current token
+    fn _ast_node(&self, name: &str, value: String) -> Node {
+        let mut node = Node::new(name.to_string(), value);
+        node.set_loc(self.tokens.position());
+        node
+    }
+
     pub fn generate_abstract_syntax_tree(&mut self) {
         let mut root_node = Node::new(
             "AbstractSyntaxTree".to_string(),
             "AbstractSyntaxTree".to_string(),
         );
         self.tokens.reset();
+        self.errors.clear();
         crate::node4m::reset_global_counter();
 
         self._ast_start(&mut root_node);
@@ -41,9 +154,14 @@ impl Par4m {
         self.abstract_syntax_tree = root_node;
     }
 
-    // <start> ::= <program> SCANEOF
+    // <start> ::= ( <program> | <module> ) SCANEOF
     fn _ast_start(&mut self, father_node: &mut Node) {
-        self._ast_program(father_node);
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "MODULE" {
+            self._ast_module(father_node);
+        } else {
+            self._ast_program(father_node);
+        }
 
         if let Some(token) = self.tokens.next_token() {
             let (token_type, _) = token;
@@ -53,402 +171,2293 @@ impl Par4m {
         }
     }
 
-    // <program> ::= BEGIN <statement_list> END
+    // <program> ::= { <import> } { <subprogram> | <extern> | <typedef>
+    //               | CONST <constant> { COMMA <constant> } SEMICOLON } BEGIN <body> END
     fn _ast_program(&mut self, father_node: &mut Node) {
-        if let Some(token) = self.tokens.next_token() {
+        self._ast_imports(father_node);
+
+        while let Some(token) = self.tokens.next_token() {
             let (token_type, _) = token;
-            if token_type.as_str() == "BEGIN" {
-                self.tokens.consume_token();
+            if token_type.as_str() == "FUNCTION" || token_type.as_str() == "PROCEDURE" {
+                self._ast_subprogram(father_node);
+            } else if token_type.as_str() == "EXTERN" {
+                self._ast_extern(father_node);
+            } else if token_type.as_str() == "TYPE" {
+                self._ast_typedef(father_node);
+            } else if token_type.as_str() == "CONST" {
+                self._ast_constants(father_node);
+            } else {
+                break;
             }
         }
 
-        self._ast_statement_list(father_node);
+        self._ast_expect("BEGIN");
+        self._ast_body(father_node);
+        self._ast_expect("END");
+    }
 
-        if let Some(token) = self.tokens.next_token() {
-            let (token_type, _) = token;
-            if token_type.as_str() == "END" {
-                self.tokens.consume_token();
-            }
+    // Consume the expected token, or report the one found instead
+    fn _ast_expect(&mut self, expected: &str) {
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == expected {
+            self.tokens.consume_token();
+        } else if token_type.as_str() == "SCANEOF" {
+            self._error(format!("expected {}, found end of file", expected));
+        } else {
+            self._error(format!("expected {}, found `{}`", expected, token_value));
         }
     }
 
-    // <statement_list> ::= <statement> { <statement> }
-    fn _ast_statement_list(&mut self, father_node: &mut Node) {
-        let mut statement_list_node = Node::new(
-            "<statement list>".to_string(),
-            "<statement list>".to_string(),
-        );
+    // <module> ::= MODULE ID SEMICOLON { <import> } { [ EXPORT ] <item> | <extern> }
+    // <item> ::= <subprogram> | <typedef> | CONST <constant> { COMMA <constant> } SEMICOLON
+    // The MODULE node comes first and holds an ID for every exported name
+    fn _ast_module(&mut self, father_node: &mut Node) {
+        self.tokens.consume_token();
+
+        let (_, token_value) = self.tokens.next_token().unwrap();
+        let mut module_node = self._ast_node("MODULE", token_value);
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_imports(father_node);
 
-        self._ast_statement(&mut statement_list_node);
         while let Some(token) = self.tokens.next_token() {
-            let (token_type, _) = token;
-            if token_type.as_str() == "ID"
-                || token_type.as_str() == "READ"
-                || token_type.as_str() == "WRITE"
-            {
-                self._ast_statement(&mut statement_list_node);
-            } else {
-                break;
+            let (mut token_type, _) = token;
+            let exported = token_type.as_str() == "EXPORT";
+            if exported {
+                self.tokens.consume_token();
+                token_type = self.tokens.next_token().unwrap().0;
+            }
+
+            match token_type.as_str() {
+                "FUNCTION" | "PROCEDURE" => self._ast_subprogram(father_node),
+                "TYPE" => self._ast_typedef(father_node),
+                "CONST" => self._ast_constants(father_node),
+                "EXTERN" if !exported => self._ast_extern(father_node),
+                _ => break,
+            }
+
+            if exported {
+                let item = father_node.children().last().unwrap();
+                let names = if item.name() == "CONST" {
+                    item.children().iter().map(Node::value).collect()
+                } else {
+                    vec![item.value()]
+                };
+                for name in names {
+                    let mut id_node = Node::new("ID".to_string(), name.to_string());
+                    id_node.set_loc(item.loc());
+                    module_node.add_child(id_node);
+                }
             }
         }
 
-        father_node.add_child(statement_list_node);
+        father_node.children_mut().insert(0, module_node);
     }
 
-    // <statement> ::= ID ASSIGNOP <expression> SEMICOLON
-    //              | READ LPAREN <id_list> RPAREN SEMICOLON
-    //              | WRITE LPAREN <expression_list> RPAREN SEMICOLON
-    fn _ast_statement(&mut self, father_node: &mut Node) {
-        let (token_type, _) = self.tokens.next_token().unwrap();
+    // <import> ::= IMPORT ID SEMICOLON
+    fn _ast_imports(&mut self, father_node: &mut Node) {
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if token_type.as_str() != "IMPORT" {
+                break;
+            }
+            self.tokens.consume_token();
 
-        match token_type.as_str() {
-            "ID" => self._ast_statement_id(father_node),
-            "READ" => self._ast_statement_read(father_node),
-            "WRITE" => self._ast_statement_write(father_node),
-            _ => (),
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "ID" {
+                father_node.add_child(self._ast_node("IMPORT", token_value));
+                self.tokens.consume_token();
+            }
+
+            let (token_type, _) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "SEMICOLON" {
+                self.tokens.consume_token();
+            }
         }
     }
 
-    // <statement> ::= ID ASSIGNOP <expression> SEMICOLON
-    fn _ast_statement_id(&mut self, father_node: &mut Node) {
-        let mut assign_op_node = Node::new("ASSIGNOP".to_string(), ":=".to_string());
+    // <subprogram> ::= FUNCTION ID LPAREN [ <parameter_list> ] RPAREN <body> END
+    //               | PROCEDURE ID LPAREN [ <parameter_list> ] RPAREN <body> END
+    fn _ast_subprogram(&mut self, father_node: &mut Node) {
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        self.tokens.consume_token();
 
         let (_, token_value) = self.tokens.next_token().unwrap();
-        assign_op_node.add_child(Node::new("ID".to_string(), token_value));
+        let mut subprogram_node = self._ast_node(&token_type, token_value);
         self.tokens.consume_token();
 
+        let mut parameter_list_node = Node::new(
+            "<parameter list>".to_string(),
+            "<parameter list>".to_string(),
+        );
+
         let (token_type, _) = self.tokens.next_token().unwrap();
-        if token_type.as_str() == "ASSIGNOP" {
+        if token_type.as_str() == "LPAREN" {
             self.tokens.consume_token();
         }
 
-        self._ast_expression(&mut assign_op_node);
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ID" {
+            self._ast_parameter_list(&mut parameter_list_node);
+        }
 
         let (token_type, _) = self.tokens.next_token().unwrap();
-        if token_type.as_str() == "SEMICOLON" {
+        if token_type.as_str() == "RPAREN" {
             self.tokens.consume_token();
         }
 
-        father_node.add_child(assign_op_node);
+        subprogram_node.add_child(parameter_list_node);
+
+        self._ast_body(&mut subprogram_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "END" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(subprogram_node);
     }
 
-    // <statement> ::= READ LPAREN <id_list> RPAREN SEMICOLON
-    fn _ast_statement_read(&mut self, father_node: &mut Node) {
-        let mut read_node = Node::new("READ".to_string(), "read".to_string());
+    // <extern> ::= EXTERN FUNCTION ID LPAREN [ <type_list> ] RPAREN COLON <type> SEMICOLON
+    //           | EXTERN PROCEDURE ID LPAREN [ <type_list> ] RPAREN SEMICOLON
+    // The EXTERN node holds the parameter TYPEs, then the result TYPE of a
+    // function
+    fn _ast_extern(&mut self, father_node: &mut Node) {
+        self.tokens.consume_token();
+
+        let (kind, _) = self.tokens.next_token().unwrap();
+        if kind.as_str() == "FUNCTION" || kind.as_str() == "PROCEDURE" {
+            self.tokens.consume_token();
+        }
+
+        let (_, token_value) = self.tokens.next_token().unwrap();
+        let mut extern_node = self._ast_node("EXTERN", token_value);
         self.tokens.consume_token();
 
+        let mut parameter_list_node = Node::new(
+            "<parameter list>".to_string(),
+            "<parameter list>".to_string(),
+        );
+
         let (token_type, _) = self.tokens.next_token().unwrap();
         if token_type.as_str() == "LPAREN" {
             self.tokens.consume_token();
         }
 
-        self._ast_id_list(&mut read_node);
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() != "RPAREN" {
+            self._ast_type_list(&mut parameter_list_node);
+        }
 
         let (token_type, _) = self.tokens.next_token().unwrap();
         if token_type.as_str() == "RPAREN" {
             self.tokens.consume_token();
         }
 
+        extern_node.add_child(parameter_list_node);
+
+        if kind.as_str() == "FUNCTION" {
+            let (token_type, _) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "COLON" {
+                self.tokens.consume_token();
+            }
+            self._ast_type(&mut extern_node);
+        }
+
         let (token_type, _) = self.tokens.next_token().unwrap();
         if token_type.as_str() == "SEMICOLON" {
             self.tokens.consume_token();
         }
 
-        father_node.add_child(read_node);
+        father_node.add_child(extern_node);
     }
 
-    // <statement> ::= WRITE LPAREN <expression_list> RPAREN SEMICOLON
-    fn _ast_statement_write(&mut self, father_node: &mut Node) {
-        let mut write_node = Node::new("WRITE".to_string(), "write".to_string());
-        self.tokens.consume_token();
+    // <type_list> ::= <type> { COMMA <type> }
+    fn _ast_type_list(&mut self, father_node: &mut Node) {
+        self._ast_type(father_node);
 
-        self.tokens.consume_token();
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if token_type.as_str() == "COMMA" {
+                self.tokens.consume_token();
+                self._ast_type(father_node);
+            } else {
+                break;
+            }
+        }
+    }
 
-        self._ast_expression_list(&mut write_node);
+    // <parameter_list> ::= <parameter> { COMMA <parameter> }
+    fn _ast_parameter_list(&mut self, father_node: &mut Node) {
+        self._ast_parameter(father_node);
 
-        self.tokens.consume_token();
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if token_type.as_str() == "COMMA" {
+                self.tokens.consume_token();
+                self._ast_parameter(father_node);
+            } else {
+                break;
+            }
+        }
+    }
 
+    // <parameter> ::= ID [ COLON <type> ]
+    // The ID holds the TYPE if one is given
+    fn _ast_parameter(&mut self, father_node: &mut Node) {
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() != "ID" {
+            return;
+        }
+        let mut id_node = self._ast_node("ID", token_value);
         self.tokens.consume_token();
 
-        father_node.add_child(write_node);
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "COLON" {
+            self.tokens.consume_token();
+            self._ast_type(&mut id_node);
+        }
+
+        father_node.add_child(id_node);
     }
 
-    // <id_list> ::= ID { COMMA ID }
-    fn _ast_id_list(&mut self, father_node: &mut Node) {
+    // <typedef> ::= TYPE ID EQOP <type> SEMICOLON
+    fn _ast_typedef(&mut self, father_node: &mut Node) {
+        self.tokens.consume_token();
+
         let (token_type, token_value) = self.tokens.next_token().unwrap();
-        if token_type.as_str() == "ID" {
-            father_node.add_child(Node::new("ID".to_string(), token_value));
+        if token_type.as_str() != "ID" {
+            return;
+        }
+        let mut typedef_node = self._ast_node("TYPEDEF", token_value);
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "EQOP" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_type(&mut typedef_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
             self.tokens.consume_token();
         }
 
+        father_node.add_child(typedef_node);
+    }
+
+    // <body> ::= { <declaration> } <statement_list>
+    // Declarations become the first children of the body's statement list
+    fn _ast_body(&mut self, father_node: &mut Node) {
+        let mut statement_list_node = Node::new(
+            "<statement list>".to_string(),
+            "<statement list>".to_string(),
+        );
+
         while let Some(token) = self.tokens.next_token() {
             let (token_type, _) = token;
-            if token_type.as_str() == "COMMA" {
-                self.tokens.consume_token();
-                let (token_type, token_value) = self.tokens.next_token().unwrap();
-                if token_type.as_str() == "ID" {
-                    father_node.add_child(Node::new("ID".to_string(), token_value));
-                    self.tokens.consume_token();
-                }
+            if token_type.as_str() == "DECLARE" {
+                self._ast_declaration(&mut statement_list_node);
+            } else if token_type.as_str() == "CONST" {
+                self._ast_constants(&mut statement_list_node);
             } else {
                 break;
             }
         }
+
+        self._ast_statements(&mut statement_list_node);
+
+        father_node.add_child(statement_list_node);
+    }
+
+    // <declaration> ::= DECLARE <id_list> COLON <type> SEMICOLON
+    //                 | CONST <constant> { COMMA <constant> } SEMICOLON
+    fn _ast_declaration(&mut self, father_node: &mut Node) {
+        let mut declare_node = self._ast_node("DECLARE", "declare".to_string());
+        self.tokens.consume_token();
+
+        self._ast_id_list(&mut declare_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "COLON" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_type(&mut declare_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(declare_node);
     }
 
-    // <expression_list> ::= <expression> { COMMA <expression> }
-    fn _ast_expression_list(&mut self, father_node: &mut Node) {
-        self._ast_expression(father_node);
+    // <declaration> ::= CONST <constant> { COMMA <constant> } SEMICOLON
+    fn _ast_constants(&mut self, father_node: &mut Node) {
+        let mut const_node = self._ast_node("CONST", "const".to_string());
+        self.tokens.consume_token();
 
-        while let Some(token) = self.tokens.next_token() {
-            let (token_type, _) = token;
+        self._ast_constant(&mut const_node);
+        while let Some((token_type, _)) = self.tokens.next_token() {
             if token_type.as_str() == "COMMA" {
                 self.tokens.consume_token();
-                self._ast_expression(father_node);
+                self._ast_constant(&mut const_node);
             } else {
                 break;
             }
         }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(const_node);
+    }
+
+    // <constant> ::= ID EQOP <expression>
+    // The CONSTANT node is named after the constant and holds its value
+    fn _ast_constant(&mut self, father_node: &mut Node) {
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() != "ID" {
+            return;
+        }
+        let mut constant_node = self._ast_node("CONSTANT", token_value);
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "EQOP" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_expression(&mut constant_node);
+
+        father_node.add_child(constant_node);
+    }
+
+    // <type> ::= INTEGER | LONG | REAL | BOOLEAN
+    //          | ARRAY LBRACKET ( INTLITERAL | ID ) RBRACKET OF <type>
+    //          | RECORD <field_group> { SEMICOLON <field_group> } [ SEMICOLON ] END
+    //          | ID
+    // A named type is a TYPE holding the name
+    fn _ast_type(&mut self, father_node: &mut Node) {
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if Self::_is_type(&token_type) {
+            father_node.add_child(self._ast_node("TYPE", token_type));
+            self.tokens.consume_token();
+        } else if token_type.as_str() == "ARRAY" {
+            let mut array_node = self._ast_node("TYPE", token_type);
+            self.tokens.consume_token();
+
+            let (token_type, _) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "LBRACKET" {
+                self.tokens.consume_token();
+            }
+
+            let (token_type, _) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "ID" {
+                self._ast_primary_id(&mut array_node);
+            } else {
+                self._ast_primary_intliteral(&mut array_node);
+            }
+
+            let (token_type, _) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "RBRACKET" {
+                self.tokens.consume_token();
+            }
+
+            let (token_type, _) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "OF" {
+                self.tokens.consume_token();
+            }
+
+            self._ast_type(&mut array_node);
+
+            father_node.add_child(array_node);
+        } else if token_type.as_str() == "RECORD" {
+            let mut record_node = self._ast_node("TYPE", token_type);
+            self.tokens.consume_token();
+
+            while let Some((token_type, _)) = self.tokens.next_token() {
+                if token_type.as_str() == "ID" {
+                    self._ast_field_group(&mut record_node);
+                } else if token_type.as_str() == "SEMICOLON" {
+                    self.tokens.consume_token();
+                } else {
+                    break;
+                }
+            }
+
+            let (token_type, _) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "END" {
+                self.tokens.consume_token();
+            }
+
+            father_node.add_child(record_node);
+        } else if token_type.as_str() == "ID" {
+            let (_, token_value) = self.tokens.next_token().unwrap();
+            father_node.add_child(self._ast_node("TYPE", token_value));
+            self.tokens.consume_token();
+        }
+    }
+
+    // <field_group> ::= <id_list> COLON <type>
+    // A FIELDS node holds the IDs, then their TYPE
+    fn _ast_field_group(&mut self, father_node: &mut Node) {
+        let mut fields_node = self._ast_node("FIELDS", "fields".to_string());
+
+        self._ast_id_list(&mut fields_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "COLON" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_type(&mut fields_node);
+
+        father_node.add_child(fields_node);
+    }
+
+    // <statement_list> ::= <statement> { <statement> }
+    fn _ast_statement_list(&mut self, father_node: &mut Node) {
+        let mut statement_list_node = Node::new(
+            "<statement list>".to_string(),
+            "<statement list>".to_string(),
+        );
+
+        self._ast_statements(&mut statement_list_node);
+
+        father_node.add_child(statement_list_node);
+    }
+
+    fn _ast_statements(&mut self, statement_list_node: &mut Node) {
+        self._ast_statement(statement_list_node);
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if Self::_is_statement_start(&token_type) && !self._is_case_arm() {
+                self._ast_statement(statement_list_node);
+            } else {
+                break;
+            }
+        }
+    }
+
+    // <statement> ::= <target> ASSIGNOP <expression> SEMICOLON
+    //              | <target> <compound_op> <expression> SEMICOLON
+    //              | <target_list> ASSIGNOP <expression_list> SEMICOLON
+    //              | READ LPAREN <target_list> RPAREN SEMICOLON
+    //              | WRITE LPAREN <write_list> RPAREN SEMICOLON
+    //              | IF <condition> THEN <statement_list> [ ELSE <statement_list> ] ENDIF
+    //              | WHILE <condition> DO <statement_list> ENDWHILE
+    //              | FOR ID ASSIGNOP <expression> TO <expression> [ STEP <expression> ] DO <statement_list> ENDFOR
+    //              | REPEAT <statement_list> UNTIL <condition> SEMICOLON
+    //              | CASE <expression> OF <case_arm> { <case_arm> } [ ELSE <statement_list> ] ENDCASE
+    //              | RETURN [ <expression> ] SEMICOLON
+    //              | BREAK SEMICOLON
+    //              | CONTINUE SEMICOLON
+    //              | EXIT LPAREN <expression> RPAREN SEMICOLON
+    //              | ASSERT LPAREN <condition> [ COMMA STRINGLITERAL ] RPAREN SEMICOLON
+    //              | <call> SEMICOLON
+    fn _ast_statement(&mut self, father_node: &mut Node) {
+        let (token_type, _) = self.tokens.next_token().unwrap();
+
+        match token_type.as_str() {
+            "ID" if self._is_call() => self._ast_statement_call(father_node),
+            "ID" => self._ast_statement_id(father_node),
+            "READ" => self._ast_statement_read(father_node),
+            "WRITE" => self._ast_statement_write(father_node),
+            "IF" => self._ast_statement_if(father_node),
+            "WHILE" => self._ast_statement_while(father_node),
+            "FOR" => self._ast_statement_for(father_node),
+            "REPEAT" => self._ast_statement_repeat(father_node),
+            "CASE" => self._ast_statement_case(father_node),
+            "RETURN" => self._ast_statement_return(father_node),
+            "BREAK" | "CONTINUE" => self._ast_statement_jump(father_node),
+            "EXIT" => self._ast_statement_exit(father_node),
+            "ASSERT" => self._ast_statement_assert(father_node),
+            _ => (),
+        }
+    }
+
+    // <statement> ::= <target> ASSIGNOP <expression> SEMICOLON
+    //              | <target> <compound_op> <expression> SEMICOLON
+    //              | <target_list> ASSIGNOP <expression_list> SEMICOLON
+    // `x += e` becomes a COMPOUND holding the target and `x + e`, where `x` is
+    // a copy of the target; the target is only evaluated once. A parallel
+    // assignment has the list of targets and the list of values as its
+    // children.
+    fn _ast_statement_id(&mut self, father_node: &mut Node) {
+        let mut assign_op_node = self._ast_node("ASSIGNOP", ":=".to_string());
+
+        self._ast_target(&mut assign_op_node);
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "COMMA" {
+            assign_op_node.set_name("PARALLEL".to_string());
+            self._ast_statement_parallel(father_node, assign_op_node);
+            return;
+        } else if let Some(op) = Self::_compound_op(&token_type) {
+            assign_op_node.set_name("COMPOUND".to_string());
+            assign_op_node.set_value(token_value.clone());
+            let mut op_node = self._ast_node(op, token_value[..1].to_string());
+            self.tokens.consume_token();
+            op_node.add_child(assign_op_node.children()[0].deep_copy());
+            self._ast_expression(&mut op_node);
+            assign_op_node.add_child(op_node);
+        } else {
+            if token_type.as_str() == "ASSIGNOP" {
+                self.tokens.consume_token();
+            }
+            self._ast_expression(&mut assign_op_node);
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(assign_op_node);
+    }
+
+    // <statement> ::= <target_list> ASSIGNOP <expression_list> SEMICOLON
+    // `parallel_node` holds the first target, which has been parsed already
+    fn _ast_statement_parallel(&mut self, father_node: &mut Node, mut parallel_node: Node) {
+        let mut targets_node = Node::new("<target list>".to_string(), "<target list>".to_string());
+        for target in parallel_node.take_children() {
+            targets_node.add_child(target);
+        }
+        while let Some((token_type, _)) = self.tokens.next_token() {
+            if token_type.as_str() == "COMMA" {
+                self.tokens.consume_token();
+                self._ast_target(&mut targets_node);
+            } else {
+                break;
+            }
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ASSIGNOP" {
+            self.tokens.consume_token();
+        }
+
+        let mut values_node = Node::new(
+            "<expression list>".to_string(),
+            "<expression list>".to_string(),
+        );
+        self._ast_expression_list(&mut values_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            self.tokens.consume_token();
+        }
+
+        parallel_node.add_child(targets_node);
+        parallel_node.add_child(values_node);
+        father_node.add_child(parallel_node);
+    }
+
+    // <statement> ::= READ LPAREN <target_list> RPAREN SEMICOLON
+    fn _ast_statement_read(&mut self, father_node: &mut Node) {
+        let mut read_node = self._ast_node("READ", "read".to_string());
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "LPAREN" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_target_list(&mut read_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "RPAREN" {
+            self.tokens.consume_token();
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(read_node);
+    }
+
+    // <statement> ::= WRITE LPAREN <write_list> RPAREN SEMICOLON
+    fn _ast_statement_write(&mut self, father_node: &mut Node) {
+        let mut write_node = self._ast_node("WRITE", "write".to_string());
+        self.tokens.consume_token();
+
+        self.tokens.consume_token();
+
+        self._ast_write_list(&mut write_node);
+
+        self.tokens.consume_token();
+
+        self.tokens.consume_token();
+
+        father_node.add_child(write_node);
+    }
+
+    // <statement> ::= IF <condition> THEN <statement_list> [ ELSE <statement_list> ] ENDIF
+    fn _ast_statement_if(&mut self, father_node: &mut Node) {
+        let mut if_node = self._ast_node("IF", "if".to_string());
+        self.tokens.consume_token();
+
+        self._ast_condition(&mut if_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "THEN" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_statement_list(&mut if_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ELSE" {
+            self.tokens.consume_token();
+            self._ast_statement_list(&mut if_node);
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ENDIF" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(if_node);
+    }
+
+    // <statement> ::= WHILE <condition> DO <statement_list> ENDWHILE
+    fn _ast_statement_while(&mut self, father_node: &mut Node) {
+        let mut while_node = self._ast_node("WHILE", "while".to_string());
+        self.tokens.consume_token();
+
+        self._ast_condition(&mut while_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "DO" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_statement_list(&mut while_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ENDWHILE" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(while_node);
+    }
+
+    // <statement> ::= FOR ID ASSIGNOP <expression> TO <expression> [ STEP <expression> ] DO <statement_list> ENDFOR
+    fn _ast_statement_for(&mut self, father_node: &mut Node) {
+        let mut for_node = self._ast_node("FOR", "for".to_string());
+        self.tokens.consume_token();
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ID" {
+            for_node.add_child(self._ast_node("ID", token_value));
+            self.tokens.consume_token();
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ASSIGNOP" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_expression(&mut for_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "TO" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_expression(&mut for_node);
+
+        // A missing STEP counts up by one
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "STEP" {
+            self.tokens.consume_token();
+            self._ast_expression(&mut for_node);
+        } else {
+            for_node.add_child(self._ast_node("INTLITERAL", "1".to_string()));
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "DO" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_statement_list(&mut for_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ENDFOR" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(for_node);
+    }
+
+    // <statement> ::= REPEAT <statement_list> UNTIL <condition> SEMICOLON
+    fn _ast_statement_repeat(&mut self, father_node: &mut Node) {
+        let mut repeat_node = self._ast_node("REPEAT", "repeat".to_string());
+        self.tokens.consume_token();
+
+        self._ast_statement_list(&mut repeat_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "UNTIL" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_condition(&mut repeat_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(repeat_node);
+    }
+
+    // <statement> ::= CASE <expression> OF <case_arm> { <case_arm> } [ ELSE <statement_list> ] ENDCASE
+    fn _ast_statement_case(&mut self, father_node: &mut Node) {
+        let mut case_node = self._ast_node("CASE", "case".to_string());
+        self.tokens.consume_token();
+
+        self._ast_expression(&mut case_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "OF" {
+            self.tokens.consume_token();
+        }
+
+        while self._is_case_arm() {
+            self._ast_case_arm(&mut case_node);
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ELSE" {
+            self.tokens.consume_token();
+            self._ast_statement_list(&mut case_node);
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ENDCASE" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(case_node);
+    }
+
+    // <case_arm> ::= <label> { COMMA <label> } COLON <statement_list>
+    // <label> ::= INTLITERAL | ID
+    // A label named by an ID must be a constant
+    fn _ast_case_arm(&mut self, father_node: &mut Node) {
+        let mut arm_node = self._ast_node("ARM", "arm".to_string());
+
+        while let Some((token_type, token_value)) = self.tokens.next_token() {
+            if token_type.as_str() == "INTLITERAL" || token_type.as_str() == "ID" {
+                arm_node.add_child(self._ast_node(&token_type, token_value));
+                self.tokens.consume_token();
+            }
+            let (token_type, _) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "COMMA" {
+                self.tokens.consume_token();
+            } else {
+                break;
+            }
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "COLON" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_statement_list(&mut arm_node);
+
+        father_node.add_child(arm_node);
+    }
+
+    // <statement> ::= RETURN [ <expression> ] SEMICOLON
+    fn _ast_statement_return(&mut self, father_node: &mut Node) {
+        let mut return_node = self._ast_node("RETURN", "return".to_string());
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() != "SEMICOLON" {
+            self._ast_expression(&mut return_node);
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(return_node);
+    }
+
+    // <statement> ::= BREAK SEMICOLON | CONTINUE SEMICOLON
+    fn _ast_statement_jump(&mut self, father_node: &mut Node) {
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        let jump_node = self._ast_node(&token_type, token_value.to_lowercase());
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(jump_node);
+    }
+
+    // <statement> ::= EXIT LPAREN <expression> RPAREN SEMICOLON
+    fn _ast_statement_exit(&mut self, father_node: &mut Node) {
+        let mut exit_node = self._ast_node("EXIT", "exit".to_string());
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "LPAREN" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_expression(&mut exit_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "RPAREN" {
+            self.tokens.consume_token();
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(exit_node);
+    }
+
+    // <statement> ::= ASSERT LPAREN <condition> [ COMMA STRINGLITERAL ] RPAREN SEMICOLON
+    fn _ast_statement_assert(&mut self, father_node: &mut Node) {
+        let mut assert_node = self._ast_node("ASSERT", "assert".to_string());
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "LPAREN" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_condition(&mut assert_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "COMMA" {
+            self.tokens.consume_token();
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "STRINGLITERAL" {
+                let text = token_value[1..token_value.len() - 1].to_string();
+                assert_node.add_child(self._ast_node("STRINGLITERAL", text));
+                self.tokens.consume_token();
+            }
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "RPAREN" {
+            self.tokens.consume_token();
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(assert_node);
+    }
+
+    // <statement> ::= <call> SEMICOLON
+    fn _ast_statement_call(&mut self, father_node: &mut Node) {
+        self._ast_call(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            self.tokens.consume_token();
+        }
+    }
+
+    // <call> ::= ID LPAREN [ <expression_list> ] RPAREN
+    fn _ast_call(&mut self, father_node: &mut Node) {
+        let (_, token_value) = self.tokens.next_token().unwrap();
+        let mut call_node = self._ast_node("CALL", token_value);
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "LPAREN" {
+            self.tokens.consume_token();
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() != "RPAREN" {
+            self._ast_expression_list(&mut call_node);
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "RPAREN" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(call_node);
+    }
+
+    // <condition> ::= <expression>
+    fn _ast_condition(&mut self, father_node: &mut Node) {
+        self._ast_expression(father_node);
+    }
+
+    // <target> ::= ID | <index> | <field>
+    fn _ast_target(&mut self, father_node: &mut Node) {
+        if self._is_index() {
+            self._ast_index(father_node);
+        } else if self._is_field() {
+            self._ast_field(father_node);
+        } else {
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "ID" {
+                father_node.add_child(self._ast_node("ID", token_value));
+                self.tokens.consume_token();
+            }
+        }
+    }
+
+    // <write_item> ::= STRINGLITERAL | <expression>
+    // The node keeps the literal's text without the quotes
+    fn _ast_write_item(&mut self, father_node: &mut Node) {
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "STRINGLITERAL" {
+            let text = token_value[1..token_value.len() - 1].to_string();
+            father_node.add_child(self._ast_node("STRINGLITERAL", text));
+            self.tokens.consume_token();
+        } else {
+            self._ast_expression(father_node);
+        }
+    }
+
+    // <write_list> ::= <write_item> { COMMA <write_item> }
+    fn _ast_write_list(&mut self, father_node: &mut Node) {
+        self._ast_write_item(father_node);
+
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if token_type.as_str() == "COMMA" {
+                self.tokens.consume_token();
+                self._ast_write_item(father_node);
+            } else {
+                break;
+            }
+        }
+    }
+
+    // <target_list> ::= <target> { COMMA <target> }
+    fn _ast_target_list(&mut self, father_node: &mut Node) {
+        self._ast_target(father_node);
+
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if token_type.as_str() == "COMMA" {
+                self.tokens.consume_token();
+                self._ast_target(father_node);
+            } else {
+                break;
+            }
+        }
+    }
+
+    // <index> ::= ID LBRACKET <expression> RBRACKET
+    fn _ast_index(&mut self, father_node: &mut Node) {
+        let (_, token_value) = self.tokens.next_token().unwrap();
+        let mut index_node = self._ast_node("INDEX", token_value);
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "LBRACKET" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_expression(&mut index_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "RBRACKET" {
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(index_node);
+    }
+
+    // <field> ::= ID DOT ID
+    // The FIELD node is named after the field and holds the record's ID
+    fn _ast_field(&mut self, father_node: &mut Node) {
+        let (_, token_value) = self.tokens.next_token().unwrap();
+        let record_node = self._ast_node("ID", token_value);
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "DOT" {
+            self.tokens.consume_token();
+        }
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        let mut field_node = self._ast_node("FIELD", String::new());
+        if token_type.as_str() == "ID" {
+            field_node.set_value(token_value);
+            self.tokens.consume_token();
+        }
+        field_node.add_child(record_node);
+
+        father_node.add_child(field_node);
+    }
+
+    // <id_list> ::= ID { COMMA ID }
+    fn _ast_id_list(&mut self, father_node: &mut Node) {
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ID" {
+            father_node.add_child(self._ast_node("ID", token_value));
+            self.tokens.consume_token();
+        }
+
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if token_type.as_str() == "COMMA" {
+                self.tokens.consume_token();
+                let (token_type, token_value) = self.tokens.next_token().unwrap();
+                if token_type.as_str() == "ID" {
+                    father_node.add_child(self._ast_node("ID", token_value));
+                    self.tokens.consume_token();
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    // <expression_list> ::= <expression> { COMMA <expression> }
+    fn _ast_expression_list(&mut self, father_node: &mut Node) {
+        self._ast_expression(father_node);
+
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if token_type.as_str() == "COMMA" {
+                self.tokens.consume_token();
+                self._ast_expression(father_node);
+            } else {
+                break;
+            }
+        }
+    }
+
+    // Parse `operand { op operand }` for any of the given operator tokens
+    // Operators associate to the left: a - b + c is (a - b) + c
+    fn _ast_binary(
+        &mut self,
+        father_node: &mut Node,
+        operators: &[&str],
+        operand: fn(&mut Self, &mut Node),
+    ) {
+        let mut expression_node = Node::new("TMP".to_string(), "TMP".to_string());
+
+        operand(self, &mut expression_node);
+
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, token_value) = token;
+            if operators.contains(&token_type.as_str()) {
+                let mut op_node = self._ast_node(&token_type, token_value);
+                self.tokens.consume_token();
+
+                for child in expression_node.take_children() {
+                    op_node.add_child(child);
+                }
+                operand(self, &mut op_node);
+
+                expression_node.add_child(op_node);
+            } else {
+                break;
+            }
+        }
+
+        for child in expression_node.take_children() {
+            father_node.add_child(child);
+        }
+    }
+
+    // Operator precedence, loosest first, as in Pascal. AND, OR and XOR are
+    // logical on BOOLEAN operands and bitwise on integers, so `x AND 1 = 1`
+    // is `(x AND 1) = 1`, and comparisons joined by a logical operator are
+    // parenthesized: `(a > 0) AND (b > 0)`.
+    //   1. < <= > >= = <> (not associative)
+    //   2. + - OR XOR
+    //   3. * / MOD SHL SHR AND
+    //   4. NOT
+    //   5. ** (right associative)
+
+    // <expression> ::= <simple_expression> [ <rel_op> <simple_expression> ]
+    fn _ast_expression(&mut self, father_node: &mut Node) {
+        let mut relation_node = Node::new("TMP".to_string(), "TMP".to_string());
+
+        self._ast_simple_expression(&mut relation_node);
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if Self::_is_rel_op(&token_type) {
+            relation_node.set_name(token_type);
+            relation_node.set_value(token_value);
+            relation_node.set_loc(self.tokens.position());
+            self.tokens.consume_token();
+
+            self._ast_simple_expression(&mut relation_node);
+            father_node.add_child(relation_node);
+
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if Self::_is_rel_op(&token_type) {
+                self._error(format!(
+                    "comparison `{}` cannot follow a comparison; parenthesize the operands of AND, OR and XOR",
+                    token_value
+                ));
+            }
+        } else {
+            for child in relation_node.take_children() {
+                father_node.add_child(child);
+            }
+        }
+    }
+
+    // <simple_expression> ::= <term> { <add_op> <term> }
+    fn _ast_simple_expression(&mut self, father_node: &mut Node) {
+        self._ast_binary(
+            father_node,
+            &["PLUSOP", "MINUSOP", "OR", "XOR"],
+            Self::_ast_term,
+        );
+    }
+
+    // <term> ::= <factor> { <mul_op> <factor> }
+    fn _ast_term(&mut self, father_node: &mut Node) {
+        self._ast_binary(
+            father_node,
+            &["MULTIOP", "DIVIDEOP", "MOD", "SHL", "SHR", "AND"],
+            Self::_ast_factor,
+        );
+    }
+
+    // <factor> ::= NOT <factor> | <primary> [ POWOP <factor> ]
+    // POWOP associates to the right: a ** b ** c is a ** (b ** c)
+    fn _ast_factor(&mut self, father_node: &mut Node) {
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "NOT" {
+            let mut not_node = self._ast_node("NOT", token_value);
+            self.tokens.consume_token();
+            self._ast_factor(&mut not_node);
+            father_node.add_child(not_node);
+            return;
+        }
+
+        let mut factor_node = Node::new("TMP".to_string(), "TMP".to_string());
+
+        self._ast_primary(&mut factor_node);
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "POWOP" {
+            factor_node.set_name(token_type);
+            factor_node.set_value(token_value);
+            factor_node.set_loc(self.tokens.position());
+            self.tokens.consume_token();
+
+            self._ast_factor(&mut factor_node);
+            father_node.add_child(factor_node);
+        } else {
+            for child in factor_node.take_children() {
+                father_node.add_child(child);
+            }
+        }
+    }
+
+    // <primary> ::= INTLITERAL | REALLITERAL | TRUE | FALSE | ID | <index> | <field> | <call>
+    //             | LPAREN <expression> RPAREN
+    fn _ast_primary(&mut self, father_node: &mut Node) {
+        let (token_type, _) = self.tokens.next_token().unwrap();
+
+        match token_type.as_str() {
+            "INTLITERAL" => self._ast_primary_intliteral(father_node),
+            "REALLITERAL" => self._ast_primary_realliteral(father_node),
+            "TRUE" | "FALSE" => self._ast_primary_boolliteral(father_node),
+            "ID" if self._is_call() => self._ast_call(father_node),
+            "ID" if self._is_index() => self._ast_index(father_node),
+            "ID" if self._is_field() => self._ast_field(father_node),
+            "ID" => self._ast_primary_id(father_node),
+            "LPAREN" => self._ast_primary_paren(father_node),
+            _ => (),
+        }
+    }
+
+    // <primary> ::= INTLITERAL
+    fn _ast_primary_intliteral(&mut self, father_node: &mut Node) {
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "INTLITERAL" {
+            father_node.add_child(self._ast_node("INTLITERAL", token_value));
+            self.tokens.consume_token();
+        }
+    }
+
+    // <primary> ::= REALLITERAL
+    fn _ast_primary_realliteral(&mut self, father_node: &mut Node) {
+        let (_, token_value) = self.tokens.next_token().unwrap();
+        father_node.add_child(self._ast_node("REALLITERAL", token_value));
+        self.tokens.consume_token();
+    }
+
+    // <primary> ::= TRUE | FALSE
+    fn _ast_primary_boolliteral(&mut self, father_node: &mut Node) {
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        let value = token_type.to_lowercase();
+        father_node.add_child(self._ast_node("BOOLLITERAL", value));
+        self.tokens.consume_token();
+    }
+
+    // <primary> ::= ID
+    fn _ast_primary_id(&mut self, father_node: &mut Node) {
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ID" {
+            father_node.add_child(self._ast_node("ID", token_value));
+            self.tokens.consume_token();
+        }
+    }
+
+    // <primary> ::= LPAREN <expression> RPAREN
+    fn _ast_primary_paren(&mut self, father_node: &mut Node) {
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "LPAREN" {
+            self.tokens.consume_token();
+        }
+
+        self._ast_expression(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "RPAREN" {
+            self.tokens.consume_token();
+        }
+    }
+
+    /*
+    CST
+     */
+
+    pub fn generate_concrete_syntax_tree(&mut self) {
+        let mut root_node = Node::new(
+            "ConcreteSyntaxTree".to_string(),
+            "ConcreteSyntaxTree".to_string(),
+        );
+        self.tokens.reset();
+        crate::node4m::reset_global_counter();
+
+        self._start(&mut root_node);
+        self.concrete_syntax_tree = root_node;
+    }
+
+    // <start> ::= ( <program> | <module> ) SCANEOF
+    fn _start(&mut self, father_node: &mut Node) {
+        let mut start_node = Node::new("<start>".to_string(), "START".to_string());
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "MODULE" {
+            self._module(&mut start_node);
+        } else {
+            self._program(&mut start_node);
+        }
+
+        if let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if token_type.as_str() == "SCANEOF" {
+                self.tokens.consume_token();
+                start_node.add_child(Node::new("SCANEOF".to_string(), "SCANEOF".to_string()));
+            }
+        }
+
+        father_node.add_child(start_node);
+    }
+
+    // <program> ::= { <import> } { <subprogram> | <extern> | <typedef>
+    //               | CONST <constant> { COMMA <constant> } SEMICOLON } BEGIN <body> END
+    fn _program(&mut self, father_node: &mut Node) {
+        let mut program_node = Node::new("<program>".to_string(), "PROGRAM".to_string());
+
+        self._imports(&mut program_node);
+
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if token_type.as_str() == "FUNCTION" || token_type.as_str() == "PROCEDURE" {
+                self._subprogram(&mut program_node);
+            } else if token_type.as_str() == "EXTERN" {
+                self._extern(&mut program_node);
+            } else if token_type.as_str() == "TYPE" {
+                self._typedef(&mut program_node);
+            } else if token_type.as_str() == "CONST" {
+                self._declaration(&mut program_node);
+            } else {
+                break;
+            }
+        }
+
+        if let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if token_type.as_str() == "BEGIN" {
+                self.tokens.consume_token();
+                program_node.add_child(Node::new("BEGIN".to_string(), "BEGIN".to_string()));
+            }
+        }
+
+        self._body(&mut program_node);
+
+        if let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if token_type.as_str() == "END" {
+                self.tokens.consume_token();
+                program_node.add_child(Node::new("END".to_string(), "END".to_string()));
+            }
+        }
+
+        father_node.add_child(program_node);
+    }
+
+    // <subprogram> ::= FUNCTION ID LPAREN [ <parameter_list> ] RPAREN <body> END
+    //               | PROCEDURE ID LPAREN [ <parameter_list> ] RPAREN <body> END
+    fn _subprogram(&mut self, father_node: &mut Node) {
+        let mut subprogram_node = Node::new("<subprogram>".to_string(), "SUBPROGRAM".to_string());
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        subprogram_node.add_child(Node::new(token_type.clone(), token_type));
+        self.tokens.consume_token();
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ID" {
+            subprogram_node.add_child(Node::new("ID".to_string(), token_value));
+            self.tokens.consume_token();
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "LPAREN" {
+            subprogram_node.add_child(Node::new("LPAREN".to_string(), "(".to_string()));
+            self.tokens.consume_token();
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ID" {
+            self._parameter_list(&mut subprogram_node);
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "RPAREN" {
+            subprogram_node.add_child(Node::new("RPAREN".to_string(), ")".to_string()));
+            self.tokens.consume_token();
+        }
+
+        self._body(&mut subprogram_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "END" {
+            subprogram_node.add_child(Node::new("END".to_string(), "END".to_string()));
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(subprogram_node);
+    }
+
+    // <module> ::= MODULE ID SEMICOLON { <import> } { [ EXPORT ] <item> | <extern> }
+    // <item> ::= <subprogram> | <typedef> | CONST <constant> { COMMA <constant> } SEMICOLON
+    fn _module(&mut self, father_node: &mut Node) {
+        let mut module_node = Node::new("<module>".to_string(), "MODULE".to_string());
+
+        for expected in ["MODULE", "ID", "SEMICOLON"] {
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == expected {
+                module_node.add_child(Node::new(token_type, token_value));
+                self.tokens.consume_token();
+            }
+        }
+
+        self._imports(&mut module_node);
+
+        while let Some(token) = self.tokens.next_token() {
+            let (mut token_type, token_value) = token;
+            let exported = token_type.as_str() == "EXPORT";
+            if exported {
+                module_node.add_child(Node::new(token_type, token_value));
+                self.tokens.consume_token();
+                token_type = self.tokens.next_token().unwrap().0;
+            }
+
+            match token_type.as_str() {
+                "FUNCTION" | "PROCEDURE" => self._subprogram(&mut module_node),
+                "TYPE" => self._typedef(&mut module_node),
+                "CONST" => self._declaration(&mut module_node),
+                "EXTERN" if !exported => self._extern(&mut module_node),
+                _ => break,
+            }
+        }
+
+        father_node.add_child(module_node);
+    }
+
+    // <import> ::= IMPORT ID SEMICOLON
+    fn _imports(&mut self, father_node: &mut Node) {
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if token_type.as_str() != "IMPORT" {
+                break;
+            }
+
+            let mut import_node = Node::new("<import>".to_string(), "IMPORT".to_string());
+            for expected in ["IMPORT", "ID", "SEMICOLON"] {
+                let (token_type, token_value) = self.tokens.next_token().unwrap();
+                if token_type.as_str() == expected {
+                    import_node.add_child(Node::new(token_type, token_value));
+                    self.tokens.consume_token();
+                }
+            }
+
+            father_node.add_child(import_node);
+        }
+    }
+
+    // <extern> ::= EXTERN FUNCTION ID LPAREN [ <type_list> ] RPAREN COLON <type> SEMICOLON
+    //           | EXTERN PROCEDURE ID LPAREN [ <type_list> ] RPAREN SEMICOLON
+    fn _extern(&mut self, father_node: &mut Node) {
+        let mut extern_node = Node::new("<extern>".to_string(), "EXTERN".to_string());
+
+        for expected in ["EXTERN", "FUNCTION", "ID", "LPAREN"] {
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == expected
+                || (expected == "FUNCTION" && token_type.as_str() == "PROCEDURE")
+            {
+                extern_node.add_child(Node::new(token_type, token_value));
+                self.tokens.consume_token();
+            }
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() != "RPAREN" {
+            self._type_list(&mut extern_node);
+        }
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "RPAREN" {
+            extern_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+        }
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "COLON" {
+            extern_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+            self._type(&mut extern_node);
+        }
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            extern_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(extern_node);
+    }
+
+    // <type_list> ::= <type> { COMMA <type> }
+    fn _type_list(&mut self, father_node: &mut Node) {
+        self._type(father_node);
+
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, token_value) = token;
+            if token_type.as_str() == "COMMA" {
+                father_node.add_child(Node::new(token_type, token_value));
+                self.tokens.consume_token();
+                self._type(father_node);
+            } else {
+                break;
+            }
+        }
+    }
+
+    // <parameter_list> ::= <parameter> { COMMA <parameter> }
+    fn _parameter_list(&mut self, father_node: &mut Node) {
+        self._parameter(father_node);
+
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, token_value) = token;
+            if token_type.as_str() == "COMMA" {
+                father_node.add_child(Node::new(token_type, token_value));
+                self.tokens.consume_token();
+                self._parameter(father_node);
+            } else {
+                break;
+            }
+        }
+    }
+
+    // <parameter> ::= ID [ COLON <type> ]
+    fn _parameter(&mut self, father_node: &mut Node) {
+        let mut parameter_node = Node::new("<parameter>".to_string(), "PARAMETER".to_string());
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ID" {
+            parameter_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+        }
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "COLON" {
+            parameter_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+            self._type(&mut parameter_node);
+        }
+
+        father_node.add_child(parameter_node);
+    }
+
+    // <typedef> ::= TYPE ID EQOP <type> SEMICOLON
+    fn _typedef(&mut self, father_node: &mut Node) {
+        let mut typedef_node = Node::new("<typedef>".to_string(), "TYPEDEF".to_string());
+
+        for expected in ["TYPE", "ID", "EQOP"] {
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == expected {
+                typedef_node.add_child(Node::new(token_type, token_value));
+                self.tokens.consume_token();
+            }
+        }
+
+        self._type(&mut typedef_node);
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            typedef_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(typedef_node);
+    }
+
+    // <body> ::= { <declaration> } <statement_list>
+    fn _body(&mut self, father_node: &mut Node) {
+        let mut body_node = Node::new("<body>".to_string(), "BODY".to_string());
+
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if token_type.as_str() == "DECLARE" || token_type.as_str() == "CONST" {
+                self._declaration(&mut body_node);
+            } else {
+                break;
+            }
+        }
+
+        self._statement_list(&mut body_node);
+
+        father_node.add_child(body_node);
+    }
+
+    // <declaration> ::= DECLARE <id_list> COLON <type> SEMICOLON
+    //                 | CONST <constant> { COMMA <constant> } SEMICOLON
+    fn _declaration(&mut self, father_node: &mut Node) {
+        let mut declaration_node =
+            Node::new("<declaration>".to_string(), "DECLARATION".to_string());
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        let is_const = token_type.as_str() == "CONST";
+        declaration_node.add_child(Node::new(token_type, token_value));
+        self.tokens.consume_token();
+
+        if is_const {
+            self._constant(&mut declaration_node);
+            while let Some((token_type, token_value)) = self.tokens.next_token() {
+                if token_type.as_str() == "COMMA" {
+                    declaration_node.add_child(Node::new(token_type, token_value));
+                    self.tokens.consume_token();
+                    self._constant(&mut declaration_node);
+                } else {
+                    break;
+                }
+            }
+
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "SEMICOLON" {
+                declaration_node.add_child(Node::new(token_type, token_value));
+                self.tokens.consume_token();
+            }
+
+            father_node.add_child(declaration_node);
+            return;
+        }
+
+        self._id_list(&mut declaration_node);
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "COLON" {
+            declaration_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+        }
+
+        self._type(&mut declaration_node);
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            declaration_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(declaration_node);
+    }
+
+    // <constant> ::= ID EQOP <expression>
+    fn _constant(&mut self, father_node: &mut Node) {
+        let mut constant_node = Node::new("<constant>".to_string(), "CONSTANT".to_string());
+
+        for expected in ["ID", "EQOP"] {
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == expected {
+                constant_node.add_child(Node::new(token_type, token_value));
+                self.tokens.consume_token();
+            }
+        }
+
+        self._expression(&mut constant_node);
+
+        father_node.add_child(constant_node);
+    }
+
+    // <type> ::= INTEGER | LONG | REAL | BOOLEAN
+    //          | ARRAY LBRACKET ( INTLITERAL | ID ) RBRACKET OF <type>
+    //          | RECORD <field_group> { SEMICOLON <field_group> } [ SEMICOLON ] END
+    //          | ID
+    fn _type(&mut self, father_node: &mut Node) {
+        let mut type_node = Node::new("<type>".to_string(), "TYPE".to_string());
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if Self::_is_type(&token_type) {
+            type_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+        } else if token_type.as_str() == "ARRAY" {
+            type_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+
+            for expected in ["LBRACKET", "INTLITERAL", "RBRACKET", "OF"] {
+                let (token_type, token_value) = self.tokens.next_token().unwrap();
+                if token_type.as_str() == expected
+                    || (expected == "INTLITERAL" && token_type.as_str() == "ID")
+                {
+                    type_node.add_child(Node::new(token_type, token_value));
+                    self.tokens.consume_token();
+                }
+            }
+
+            self._type(&mut type_node);
+        } else if token_type.as_str() == "RECORD" {
+            type_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+
+            while let Some((token_type, token_value)) = self.tokens.next_token() {
+                if token_type.as_str() == "ID" {
+                    self._field_group(&mut type_node);
+                } else if token_type.as_str() == "SEMICOLON" {
+                    type_node.add_child(Node::new(token_type, token_value));
+                    self.tokens.consume_token();
+                } else {
+                    break;
+                }
+            }
+
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "END" {
+                type_node.add_child(Node::new(token_type, token_value));
+                self.tokens.consume_token();
+            }
+        } else if token_type.as_str() == "ID" {
+            type_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+        }
+
+        father_node.add_child(type_node);
+    }
+
+    // <field_group> ::= <id_list> COLON <type>
+    fn _field_group(&mut self, father_node: &mut Node) {
+        let mut field_group_node =
+            Node::new("<field group>".to_string(), "FIELD_GROUP".to_string());
+
+        self._id_list(&mut field_group_node);
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "COLON" {
+            field_group_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+        }
+
+        self._type(&mut field_group_node);
+
+        father_node.add_child(field_group_node);
+    }
+
+    // <statement_list> ::= <statement> { <statement> }
+    fn _statement_list(&mut self, father_node: &mut Node) {
+        let mut statement_list_node =
+            Node::new("<statement list>".to_string(), "STATEMENT_LIST".to_string());
+
+        self._statement(&mut statement_list_node);
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if Self::_is_statement_start(&token_type) && !self._is_case_arm() {
+                self._statement(&mut statement_list_node);
+            } else {
+                break;
+            }
+        }
+
+        father_node.add_child(statement_list_node);
+    }
+
+    // <statement> ::= <target> ASSIGNOP <expression> SEMICOLON
+    //              | <target> <compound_op> <expression> SEMICOLON
+    //              | <target_list> ASSIGNOP <expression_list> SEMICOLON
+    //              | READ LPAREN <target_list> RPAREN SEMICOLON
+    //              | WRITE LPAREN <write_list> RPAREN SEMICOLON
+    //              | IF <condition> THEN <statement_list> [ ELSE <statement_list> ] ENDIF
+    //              | WHILE <condition> DO <statement_list> ENDWHILE
+    //              | FOR ID ASSIGNOP <expression> TO <expression> [ STEP <expression> ] DO <statement_list> ENDFOR
+    //              | REPEAT <statement_list> UNTIL <condition> SEMICOLON
+    //              | CASE <expression> OF <case_arm> { <case_arm> } [ ELSE <statement_list> ] ENDCASE
+    //              | RETURN [ <expression> ] SEMICOLON
+    //              | BREAK SEMICOLON
+    //              | CONTINUE SEMICOLON
+    //              | EXIT LPAREN <expression> RPAREN SEMICOLON
+    //              | ASSERT LPAREN <condition> [ COMMA STRINGLITERAL ] RPAREN SEMICOLON
+    //              | <call> SEMICOLON
+    fn _statement(&mut self, father_node: &mut Node) {
+        let mut statement_node = Node::new("<statement>".to_string(), "STATEMENT".to_string());
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+
+        match token_type.as_str() {
+            "ID" if self._is_call() => self._statement_call(&mut statement_node),
+            "ID" => self._statement_id(&mut statement_node),
+            "READ" => self._statment_read(&mut statement_node),
+            "WRITE" => self._statment_write(&mut statement_node),
+            "IF" => self._statement_if(&mut statement_node),
+            "WHILE" => self._statement_while(&mut statement_node),
+            "FOR" => self._statement_for(&mut statement_node),
+            "REPEAT" => self._statement_repeat(&mut statement_node),
+            "CASE" => self._statement_case(&mut statement_node),
+            "RETURN" => self._statement_return(&mut statement_node),
+            "BREAK" | "CONTINUE" => self._statement_jump(&mut statement_node),
+            "EXIT" => self._statement_exit(&mut statement_node),
+            "ASSERT" => self._statement_assert(&mut statement_node),
+            _ => (),
+        }
+
+        father_node.add_child(statement_node);
+    }
+
+    // <statment> ::= <target> ASSIGNOP <expression> SEMICOLON
+    //             | <target> <compound_op> <expression> SEMICOLON
+    //             | <target_list> ASSIGNOP <expression_list> SEMICOLON
+    fn _statement_id(&mut self, father_node: &mut Node) {
+        self._target_list(father_node);
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ASSIGNOP" || Self::_compound_op(&token_type).is_some() {
+            self.tokens.consume_token();
+            father_node.add_child(Node::new(token_type, token_value));
+        }
+
+        self._expression_list(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            self.tokens.consume_token();
+            father_node.add_child(Node::new("SEMICOLON".to_string(), ";".to_string()));
+        }
+    }
+
+    // <statment> ::= READ LPAREN <target_list> RPAREN SEMICOLON
+    fn _statment_read(&mut self, father_node: &mut Node) {
+        father_node.add_child(Node::new("READ".to_string(), "READ".to_string()));
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "LPAREN" {
+            father_node.add_child(Node::new("LPAREN".to_string(), "(".to_string()));
+            self.tokens.consume_token();
+        }
+
+        self._target_list(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "RPAREN" {
+            father_node.add_child(Node::new("RPAREN".to_string(), ")".to_string()));
+            self.tokens.consume_token();
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            father_node.add_child(Node::new("SEMICOLON".to_string(), ";".to_string()));
+            self.tokens.consume_token();
+        }
+    }
+
+    // <statment> ::= WRITE LPAREN <write_list> RPAREN SEMICOLON
+    fn _statment_write(&mut self, father_node: &mut Node) {
+        father_node.add_child(Node::new("WRITE".to_string(), "WRITE".to_string()));
+        self.tokens.consume_token();
+
+        father_node.add_child(Node::new("LPAREN".to_string(), "(".to_string()));
+        self.tokens.consume_token();
+
+        self._write_list(father_node);
+
+        father_node.add_child(Node::new("RPAREN".to_string(), ")".to_string()));
+        self.tokens.consume_token();
+
+        father_node.add_child(Node::new("SEMICOLON".to_string(), ";".to_string()));
+        self.tokens.consume_token();
+    }
+
+    // <statement> ::= IF <condition> THEN <statement_list> [ ELSE <statement_list> ] ENDIF
+    fn _statement_if(&mut self, father_node: &mut Node) {
+        father_node.add_child(Node::new("IF".to_string(), "IF".to_string()));
+        self.tokens.consume_token();
+
+        self._condition(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "THEN" {
+            father_node.add_child(Node::new("THEN".to_string(), "THEN".to_string()));
+            self.tokens.consume_token();
+        }
+
+        self._statement_list(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ELSE" {
+            father_node.add_child(Node::new("ELSE".to_string(), "ELSE".to_string()));
+            self.tokens.consume_token();
+            self._statement_list(father_node);
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ENDIF" {
+            father_node.add_child(Node::new("ENDIF".to_string(), "ENDIF".to_string()));
+            self.tokens.consume_token();
+        }
+    }
+
+    // <statement> ::= WHILE <condition> DO <statement_list> ENDWHILE
+    fn _statement_while(&mut self, father_node: &mut Node) {
+        father_node.add_child(Node::new("WHILE".to_string(), "WHILE".to_string()));
+        self.tokens.consume_token();
+
+        self._condition(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "DO" {
+            father_node.add_child(Node::new("DO".to_string(), "DO".to_string()));
+            self.tokens.consume_token();
+        }
+
+        self._statement_list(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ENDWHILE" {
+            father_node.add_child(Node::new("ENDWHILE".to_string(), "ENDWHILE".to_string()));
+            self.tokens.consume_token();
+        }
+    }
+
+    // <statement> ::= FOR ID ASSIGNOP <expression> TO <expression> [ STEP <expression> ] DO <statement_list> ENDFOR
+    fn _statement_for(&mut self, father_node: &mut Node) {
+        father_node.add_child(Node::new("FOR".to_string(), "FOR".to_string()));
+        self.tokens.consume_token();
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ID" {
+            father_node.add_child(Node::new("ID".to_string(), token_value));
+            self.tokens.consume_token();
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ASSIGNOP" {
+            father_node.add_child(Node::new("ASSIGNOP".to_string(), ":=".to_string()));
+            self.tokens.consume_token();
+        }
+
+        self._expression(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "TO" {
+            father_node.add_child(Node::new("TO".to_string(), "TO".to_string()));
+            self.tokens.consume_token();
+        }
+
+        self._expression(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "STEP" {
+            father_node.add_child(Node::new("STEP".to_string(), "STEP".to_string()));
+            self.tokens.consume_token();
+            self._expression(father_node);
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "DO" {
+            father_node.add_child(Node::new("DO".to_string(), "DO".to_string()));
+            self.tokens.consume_token();
+        }
+
+        self._statement_list(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ENDFOR" {
+            father_node.add_child(Node::new("ENDFOR".to_string(), "ENDFOR".to_string()));
+            self.tokens.consume_token();
+        }
+    }
+
+    // <statement> ::= REPEAT <statement_list> UNTIL <condition> SEMICOLON
+    fn _statement_repeat(&mut self, father_node: &mut Node) {
+        father_node.add_child(Node::new("REPEAT".to_string(), "REPEAT".to_string()));
+        self.tokens.consume_token();
+
+        self._statement_list(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "UNTIL" {
+            father_node.add_child(Node::new("UNTIL".to_string(), "UNTIL".to_string()));
+            self.tokens.consume_token();
+        }
+
+        self._condition(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            father_node.add_child(Node::new("SEMICOLON".to_string(), ";".to_string()));
+            self.tokens.consume_token();
+        }
+    }
+
+    // <statement> ::= CASE <expression> OF <case_arm> { <case_arm> } [ ELSE <statement_list> ] ENDCASE
+    fn _statement_case(&mut self, father_node: &mut Node) {
+        father_node.add_child(Node::new("CASE".to_string(), "CASE".to_string()));
+        self.tokens.consume_token();
+
+        self._expression(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "OF" {
+            father_node.add_child(Node::new("OF".to_string(), "OF".to_string()));
+            self.tokens.consume_token();
+        }
+
+        while self._is_case_arm() {
+            self._case_arm(father_node);
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ELSE" {
+            father_node.add_child(Node::new("ELSE".to_string(), "ELSE".to_string()));
+            self.tokens.consume_token();
+            self._statement_list(father_node);
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "ENDCASE" {
+            father_node.add_child(Node::new("ENDCASE".to_string(), "ENDCASE".to_string()));
+            self.tokens.consume_token();
+        }
+    }
+
+    // <case_arm> ::= <label> { COMMA <label> } COLON <statement_list>
+    // <label> ::= INTLITERAL | ID
+    fn _case_arm(&mut self, father_node: &mut Node) {
+        let mut arm_node = Node::new("<case arm>".to_string(), "CASE_ARM".to_string());
+
+        while let Some((token_type, token_value)) = self.tokens.next_token() {
+            if token_type.as_str() == "INTLITERAL" || token_type.as_str() == "ID" {
+                arm_node.add_child(Node::new(token_type, token_value));
+                self.tokens.consume_token();
+            }
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "COMMA" {
+                arm_node.add_child(Node::new("COMMA".to_string(), token_value));
+                self.tokens.consume_token();
+            } else {
+                break;
+            }
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "COLON" {
+            arm_node.add_child(Node::new("COLON".to_string(), ":".to_string()));
+            self.tokens.consume_token();
+        }
+
+        self._statement_list(&mut arm_node);
+
+        father_node.add_child(arm_node);
+    }
+
+    // <statement> ::= RETURN [ <expression> ] SEMICOLON
+    fn _statement_return(&mut self, father_node: &mut Node) {
+        father_node.add_child(Node::new("RETURN".to_string(), "RETURN".to_string()));
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() != "SEMICOLON" {
+            self._expression(father_node);
+        }
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            father_node.add_child(Node::new("SEMICOLON".to_string(), ";".to_string()));
+            self.tokens.consume_token();
+        }
+    }
+
+    // <statement> ::= BREAK SEMICOLON | CONTINUE SEMICOLON
+    fn _statement_jump(&mut self, father_node: &mut Node) {
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        father_node.add_child(Node::new(token_type.clone(), token_type));
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            father_node.add_child(Node::new("SEMICOLON".to_string(), ";".to_string()));
+            self.tokens.consume_token();
+        }
     }
 
-    // <expression> ::= <primary> { <add_op> <primary> }
-    // CHANGE TO: <expression> ::= <primary> { <add_op> <expression> }
-    fn _ast_expression(&mut self, father_node: &mut Node) {
-        let mut add_op_node = Node::new("TMP".to_string(), "TMP".to_string());
+    // <statement> ::= EXIT LPAREN <expression> RPAREN SEMICOLON
+    fn _statement_exit(&mut self, father_node: &mut Node) {
+        father_node.add_child(Node::new("EXIT".to_string(), "EXIT".to_string()));
+        self.tokens.consume_token();
 
-        self._ast_primary(&mut add_op_node);
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "LPAREN" {
+            father_node.add_child(Node::new("LPAREN".to_string(), "(".to_string()));
+            self.tokens.consume_token();
+        }
 
-        let mut flag = false;
-        while let Some(token) = self.tokens.next_token() {
-            let (token_type, token_value) = token;
-            if token_type.as_str() == "PLUSOP" || token_type.as_str() == "MINUSOP" {
-                flag = true;
+        self._expression(father_node);
 
-                add_op_node.set_name(token_type);
-                add_op_node.set_value(token_value);
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "RPAREN" {
+            father_node.add_child(Node::new("RPAREN".to_string(), ")".to_string()));
+            self.tokens.consume_token();
+        }
 
-                self.tokens.consume_token();
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            father_node.add_child(Node::new("SEMICOLON".to_string(), ";".to_string()));
+            self.tokens.consume_token();
+        }
+    }
 
-                self._ast_expression(&mut add_op_node);
-            } else {
-                break;
-            }
+    // <statement> ::= ASSERT LPAREN <condition> [ COMMA STRINGLITERAL ] RPAREN SEMICOLON
+    fn _statement_assert(&mut self, father_node: &mut Node) {
+        father_node.add_child(Node::new("ASSERT".to_string(), "ASSERT".to_string()));
+        self.tokens.consume_token();
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "LPAREN" {
+            father_node.add_child(Node::new("LPAREN".to_string(), "(".to_string()));
+            self.tokens.consume_token();
         }
 
-        if flag {
-            father_node.add_child(add_op_node);
-        } else {
-            let children = add_op_node.take_children();
-            for child in children {
-                father_node.add_child(child);
+        self._condition(father_node);
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "COMMA" {
+            father_node.add_child(Node::new("COMMA".to_string(), token_value));
+            self.tokens.consume_token();
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "STRINGLITERAL" {
+                father_node.add_child(Node::new("STRINGLITERAL".to_string(), token_value));
+                self.tokens.consume_token();
             }
         }
-    }
 
-    // <primary> ::= INTLITERAL | ID | LPAREN <expression> RPAREN
-    fn _ast_primary(&mut self, father_node: &mut Node) {
         let (token_type, _) = self.tokens.next_token().unwrap();
-
-        match token_type.as_str() {
-            "INTLITERAL" => self._ast_primary_intliteral(father_node),
-            "ID" => self._ast_primary_id(father_node),
-            "LPAREN" => self._ast_primary_paren(father_node),
-            _ => (),
+        if token_type.as_str() == "RPAREN" {
+            father_node.add_child(Node::new("RPAREN".to_string(), ")".to_string()));
+            self.tokens.consume_token();
         }
-    }
 
-    // <primary> ::= INTLITERAL
-    fn _ast_primary_intliteral(&mut self, father_node: &mut Node) {
-        let (token_type, token_value) = self.tokens.next_token().unwrap();
-        if token_type.as_str() == "INTLITERAL" {
-            father_node.add_child(Node::new("INTLITERAL".to_string(), token_value));
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            father_node.add_child(Node::new("SEMICOLON".to_string(), ";".to_string()));
             self.tokens.consume_token();
         }
     }
 
-    // <primary> ::= ID
-    fn _ast_primary_id(&mut self, father_node: &mut Node) {
-        let (token_type, token_value) = self.tokens.next_token().unwrap();
-        if token_type.as_str() == "ID" {
-            father_node.add_child(Node::new("ID".to_string(), token_value));
+    // <statement> ::= <call> SEMICOLON
+    fn _statement_call(&mut self, father_node: &mut Node) {
+        self._call(father_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "SEMICOLON" {
+            father_node.add_child(Node::new("SEMICOLON".to_string(), ";".to_string()));
             self.tokens.consume_token();
         }
     }
 
-    // <primary> ::= LPAREN <expression> RPAREN
-    fn _ast_primary_paren(&mut self, father_node: &mut Node) {
+    // <call> ::= ID LPAREN [ <expression_list> ] RPAREN
+    fn _call(&mut self, father_node: &mut Node) {
+        let mut call_node = Node::new("<call>".to_string(), "CALL".to_string());
+
+        let (_, token_value) = self.tokens.next_token().unwrap();
+        call_node.add_child(Node::new("ID".to_string(), token_value));
+        self.tokens.consume_token();
+
         let (token_type, _) = self.tokens.next_token().unwrap();
         if token_type.as_str() == "LPAREN" {
+            call_node.add_child(Node::new("LPAREN".to_string(), "(".to_string()));
             self.tokens.consume_token();
         }
 
-        self._ast_expression(father_node);
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if token_type.as_str() != "RPAREN" {
+            self._expression_list(&mut call_node);
+        }
 
         let (token_type, _) = self.tokens.next_token().unwrap();
         if token_type.as_str() == "RPAREN" {
+            call_node.add_child(Node::new("RPAREN".to_string(), ")".to_string()));
             self.tokens.consume_token();
         }
+
+        father_node.add_child(call_node);
     }
 
-    /*
-    CST
-     */
+    // <condition> ::= <expression>
+    fn _condition(&mut self, father_node: &mut Node) {
+        let mut condition_node = Node::new("<condition>".to_string(), "CONDITION".to_string());
 
-    pub fn generate_concrete_syntax_tree(&mut self) {
-        let mut root_node = Node::new(
-            "ConcreteSyntaxTree".to_string(),
-            "ConcreteSyntaxTree".to_string(),
-        );
-        self.tokens.reset();
-        crate::node4m::reset_global_counter();
+        self._expression(&mut condition_node);
 
-        self._start(&mut root_node);
-        self.concrete_syntax_tree = root_node;
+        father_node.add_child(condition_node);
     }
 
-    // <start> ::= <program> SCANEOF
-    fn _start(&mut self, father_node: &mut Node) {
-        let mut start_node = Node::new("<start>".to_string(), "START".to_string());
+    // <target> ::= ID | <index> | <field>
+    fn _target(&mut self, father_node: &mut Node) {
+        let mut target_node = Node::new("<target>".to_string(), "TARGET".to_string());
 
-        self._program(&mut start_node);
-
-        if let Some(token) = self.tokens.next_token() {
-            let (token_type, _) = token;
-            if token_type.as_str() == "SCANEOF" {
+        if self._is_index() {
+            self._index(&mut target_node);
+        } else if self._is_field() {
+            self._field(&mut target_node);
+        } else {
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == "ID" {
+                target_node.add_child(Node::new("ID".to_string(), token_value));
                 self.tokens.consume_token();
-                start_node.add_child(Node::new("SCANEOF".to_string(), "SCANEOF".to_string()));
             }
         }
 
-        father_node.add_child(start_node);
+        father_node.add_child(target_node);
     }
 
-    // <program> ::= BEGIN <statement_list> END
-    fn _program(&mut self, father_node: &mut Node) {
-        let mut program_node = Node::new("<program>".to_string(), "PROGRAM".to_string());
+    // <write_item> ::= STRINGLITERAL | <expression>
+    fn _write_item(&mut self, father_node: &mut Node) {
+        let mut write_item_node = Node::new("<write item>".to_string(), "WRITE_ITEM".to_string());
 
-        if let Some(token) = self.tokens.next_token() {
-            let (token_type, _) = token;
-            if token_type.as_str() == "BEGIN" {
-                self.tokens.consume_token();
-                program_node.add_child(Node::new("BEGIN".to_string(), "BEGIN".to_string()));
-            }
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "STRINGLITERAL" {
+            write_item_node.add_child(Node::new("STRINGLITERAL".to_string(), token_value));
+            self.tokens.consume_token();
+        } else {
+            self._expression(&mut write_item_node);
         }
 
-        self._statement_list(&mut program_node);
+        father_node.add_child(write_item_node);
+    }
 
-        if let Some(token) = self.tokens.next_token() {
-            let (token_type, _) = token;
-            if token_type.as_str() == "END" {
+    // <write_list> ::= <write_item> { COMMA <write_item> }
+    fn _write_list(&mut self, father_node: &mut Node) {
+        let mut write_list_node = Node::new("<write list>".to_string(), "WRITE_LIST".to_string());
+
+        self._write_item(&mut write_list_node);
+
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, token_value) = token;
+            if token_type.as_str() == "COMMA" {
+                write_list_node.add_child(Node::new("COMMA".to_string(), token_value));
                 self.tokens.consume_token();
-                program_node.add_child(Node::new("END".to_string(), "END".to_string()));
+                self._write_item(&mut write_list_node);
+            } else {
+                break;
             }
         }
 
-        father_node.add_child(program_node);
+        father_node.add_child(write_list_node);
     }
 
-    // <statement_list> ::= <statement> { <statement> }
-    fn _statement_list(&mut self, father_node: &mut Node) {
-        let mut statement_list_node =
-            Node::new("<statement list>".to_string(), "STATEMENT_LIST".to_string());
+    // <target_list> ::= <target> { COMMA <target> }
+    fn _target_list(&mut self, father_node: &mut Node) {
+        self._target(father_node);
 
-        self._statement(&mut statement_list_node);
         while let Some(token) = self.tokens.next_token() {
-            let (token_type, _) = token;
-            if token_type.as_str() == "ID"
-                || token_type.as_str() == "READ"
-                || token_type.as_str() == "WRITE"
-            {
-                self._statement(&mut statement_list_node);
+            let (token_type, token_value) = token;
+            if token_type.as_str() == "COMMA" {
+                father_node.add_child(Node::new("COMMA".to_string(), token_value));
+                self.tokens.consume_token();
+                self._target(father_node);
             } else {
                 break;
             }
         }
-
-        father_node.add_child(statement_list_node);
     }
 
-    // <statement> ::= ID ASSIGNOP <expression> SEMICOLON
-    //              | READ LPAREN <id_list> RPAREN SEMICOLON
-    //              | WRITE LPAREN <expression_list> RPAREN SEMICOLON
-    fn _statement(&mut self, father_node: &mut Node) {
-        let mut statement_node = Node::new("<statement>".to_string(), "STATEMENT".to_string());
-
-        let (token_type, _) = self.tokens.next_token().unwrap();
-
-        match token_type.as_str() {
-            "ID" => self._statement_id(&mut statement_node),
-            "READ" => self._statment_read(&mut statement_node),
-            "WRITE" => self._statment_write(&mut statement_node),
-            _ => (),
-        }
-
-        father_node.add_child(statement_node);
-    }
+    // <index> ::= ID LBRACKET <expression> RBRACKET
+    fn _index(&mut self, father_node: &mut Node) {
+        let mut index_node = Node::new("<index>".to_string(), "INDEX".to_string());
 
-    // <statment> ::= ID ASSIGNOP <expression> SEMICOLON
-    fn _statement_id(&mut self, father_node: &mut Node) {
         let (_, token_value) = self.tokens.next_token().unwrap();
-        father_node.add_child(Node::new("ID".to_string(), token_value));
-        self.tokens.consume_token();
-
-        let (token_type, _) = self.tokens.next_token().unwrap();
-        if token_type.as_str() == "ASSIGNOP" {
-            self.tokens.consume_token();
-            father_node.add_child(Node::new("ASSIGNOP".to_string(), ":=".to_string()));
-        }
-
-        self._expression(father_node);
-
-        let (token_type, _) = self.tokens.next_token().unwrap();
-        if token_type.as_str() == "SEMICOLON" {
-            self.tokens.consume_token();
-            father_node.add_child(Node::new("SEMICOLON".to_string(), ";".to_string()));
-        }
-    }
-
-    // <statment> ::= READ LPAREN <id_list> RPAREN SEMICOLON
-    fn _statment_read(&mut self, father_node: &mut Node) {
-        father_node.add_child(Node::new("READ".to_string(), "READ".to_string()));
+        index_node.add_child(Node::new("ID".to_string(), token_value));
         self.tokens.consume_token();
 
-        let (token_type, _) = self.tokens.next_token().unwrap();
-        if token_type.as_str() == "LPAREN" {
-            father_node.add_child(Node::new("LPAREN".to_string(), "(".to_string()));
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "LBRACKET" {
+            index_node.add_child(Node::new(token_type, token_value));
             self.tokens.consume_token();
         }
 
-        self._id_list(father_node);
+        self._expression(&mut index_node);
 
-        let (token_type, _) = self.tokens.next_token().unwrap();
-        if token_type.as_str() == "RPAREN" {
-            father_node.add_child(Node::new("RPAREN".to_string(), ")".to_string()));
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "RBRACKET" {
+            index_node.add_child(Node::new(token_type, token_value));
             self.tokens.consume_token();
         }
 
-        let (token_type, _) = self.tokens.next_token().unwrap();
-        if token_type.as_str() == "SEMICOLON" {
-            father_node.add_child(Node::new("SEMICOLON".to_string(), ";".to_string()));
-            self.tokens.consume_token();
-        }
+        father_node.add_child(index_node);
     }
 
-    // <statment> ::= WRITE LPAREN <expression_list> RPAREN SEMICOLON
-    fn _statment_write(&mut self, father_node: &mut Node) {
-        father_node.add_child(Node::new("WRITE".to_string(), "WRITE".to_string()));
-        self.tokens.consume_token();
-
-        father_node.add_child(Node::new("LPAREN".to_string(), "(".to_string()));
-        self.tokens.consume_token();
-
-        self._expression_list(father_node);
+    // <field> ::= ID DOT ID
+    fn _field(&mut self, father_node: &mut Node) {
+        let mut field_node = Node::new("<field>".to_string(), "FIELD".to_string());
 
-        father_node.add_child(Node::new("RPAREN".to_string(), ")".to_string()));
-        self.tokens.consume_token();
+        for expected in ["ID", "DOT", "ID"] {
+            let (token_type, token_value) = self.tokens.next_token().unwrap();
+            if token_type.as_str() == expected {
+                field_node.add_child(Node::new(token_type, token_value));
+                self.tokens.consume_token();
+            }
+        }
 
-        father_node.add_child(Node::new("SEMICOLON".to_string(), ";".to_string()));
-        self.tokens.consume_token();
+        father_node.add_child(field_node);
     }
 
     // <id_list> ::= ID { COMMA ID }
@@ -498,26 +2507,89 @@ impl Par4m {
         father_node.add_child(expression_list_node);
     }
 
-    // <expression> ::= <primary> { <add_op> <primary> }
+    // <expression> ::= <simple_expression> [ <rel_op> <simple_expression> ]
     fn _expression(&mut self, father_node: &mut Node) {
         let mut expression_node = Node::new("<expression>".to_string(), "EXPRESSION".to_string());
 
-        self._primary(&mut expression_node);
+        self._simple_expression(&mut expression_node);
+
+        let (token_type, _) = self.tokens.next_token().unwrap();
+        if Self::_is_rel_op(&token_type) {
+            self._rel_op(&mut expression_node);
+            self._simple_expression(&mut expression_node);
+        }
+
+        father_node.add_child(expression_node);
+    }
+
+    // <simple_expression> ::= <term> { <add_op> <term> }
+    fn _simple_expression(&mut self, father_node: &mut Node) {
+        let mut simple_expression_node = Node::new(
+            "<simple expression>".to_string(),
+            "SIMPLE_EXPRESSION".to_string(),
+        );
+
+        self._term(&mut simple_expression_node);
 
         while let Some(token) = self.tokens.next_token() {
             let (token_type, _) = token;
-            if token_type.as_str() == "PLUSOP" || token_type.as_str() == "MINUSOP" {
-                self._add_op(&mut expression_node);
-                self._primary(&mut expression_node);
+            if Self::_is_add_op(&token_type) {
+                self._add_op(&mut simple_expression_node);
+                self._term(&mut simple_expression_node);
             } else {
                 break;
             }
         }
 
-        father_node.add_child(expression_node);
+        father_node.add_child(simple_expression_node);
+    }
+
+    // <term> ::= <factor> { <mul_op> <factor> }
+    fn _term(&mut self, father_node: &mut Node) {
+        let mut term_node = Node::new("<term>".to_string(), "TERM".to_string());
+
+        self._factor(&mut term_node);
+
+        while let Some(token) = self.tokens.next_token() {
+            let (token_type, _) = token;
+            if Self::_is_mul_op(&token_type) {
+                self._mul_op(&mut term_node);
+                self._factor(&mut term_node);
+            } else {
+                break;
+            }
+        }
+
+        father_node.add_child(term_node);
+    }
+
+    // <factor> ::= NOT <factor> | <primary> [ POWOP <factor> ]
+    fn _factor(&mut self, father_node: &mut Node) {
+        let mut factor_node = Node::new("<factor>".to_string(), "FACTOR".to_string());
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "NOT" {
+            factor_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+            self._factor(&mut factor_node);
+            father_node.add_child(factor_node);
+            return;
+        }
+
+        self._primary(&mut factor_node);
+
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        if token_type.as_str() == "POWOP" {
+            factor_node.add_child(Node::new(token_type, token_value));
+            self.tokens.consume_token();
+            self._factor(&mut factor_node);
+        }
+
+        father_node.add_child(factor_node);
     }
 
-    // <primary> ::= INTLITERAL | ID | LPAREN <expression> RPAREN
+    // <primary> ::= INTLITERAL | REALLITERAL | TRUE | FALSE | ID | <index> | <field> | <call>
+    //             | LPAREN <expression> RPAREN
     fn _primary(&mut self, father_node: &mut Node) {
         let mut primary_node = Node::new("<primary>".to_string(), "PRIMARY".to_string());
 
@@ -525,6 +2597,11 @@ impl Par4m {
 
         match token_type.as_str() {
             "INTLITERAL" => self._primary_intliteral(&mut primary_node),
+            "REALLITERAL" => self._primary_realliteral(&mut primary_node),
+            "TRUE" | "FALSE" => self._primary_boolliteral(&mut primary_node),
+            "ID" if self._is_call() => self._call(&mut primary_node),
+            "ID" if self._is_index() => self._index(&mut primary_node),
+            "ID" if self._is_field() => self._field(&mut primary_node),
             "ID" => self._primary_id(&mut primary_node),
             "LPAREN" => self._primary_paren(&mut primary_node),
             _ => (),
@@ -542,6 +2619,20 @@ impl Par4m {
         }
     }
 
+    // <primary> ::= REALLITERAL
+    fn _primary_realliteral(&mut self, father_node: &mut Node) {
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        father_node.add_child(Node::new(token_type, token_value));
+        self.tokens.consume_token();
+    }
+
+    // <primary> ::= TRUE | FALSE
+    fn _primary_boolliteral(&mut self, father_node: &mut Node) {
+        let (token_type, token_value) = self.tokens.next_token().unwrap();
+        father_node.add_child(Node::new(token_type, token_value));
+        self.tokens.consume_token();
+    }
+
     // <primary> ::= ID
     fn _primary_id(&mut self, father_node: &mut Node) {
         let (token_type, token_value) = self.tokens.next_token().unwrap();
@@ -568,13 +2659,13 @@ impl Par4m {
         }
     }
 
-    // <add_op> ::= PLUSOP | MINUSOP
+    // <add_op> ::= PLUSOP | MINUSOP | OR | XOR
     fn _add_op(&mut self, father_node: &mut Node) {
         let mut add_op_node = Node::new("<addop>".to_string(), "ADDOP".to_string());
 
         if let Some(token) = self.tokens.next_token() {
             let (token_type, token_value) = token;
-            if token_type.as_str() == "PLUSOP" || token_type.as_str() == "MINUSOP" {
+            if Self::_is_add_op(&token_type) {
                 add_op_node.add_child(Node::new(token_type, token_value));
                 self.tokens.consume_token();
             }
@@ -582,4 +2673,34 @@ impl Par4m {
 
         father_node.add_child(add_op_node);
     }
+
+    // <mul_op> ::= MULTIOP | DIVIDEOP | MOD | SHL | SHR | AND
+    fn _mul_op(&mut self, father_node: &mut Node) {
+        let mut mul_op_node = Node::new("<mulop>".to_string(), "MULOP".to_string());
+
+        if let Some(token) = self.tokens.next_token() {
+            let (token_type, token_value) = token;
+            if Self::_is_mul_op(&token_type) {
+                mul_op_node.add_child(Node::new(token_type, token_value));
+                self.tokens.consume_token();
+            }
+        }
+
+        father_node.add_child(mul_op_node);
+    }
+
+    // <rel_op> ::= LTOP | LEOP | GTOP | GEOP | EQOP | NEOP
+    fn _rel_op(&mut self, father_node: &mut Node) {
+        let mut rel_op_node = Node::new("<relop>".to_string(), "RELOP".to_string());
+
+        if let Some(token) = self.tokens.next_token() {
+            let (token_type, token_value) = token;
+            if Self::_is_rel_op(&token_type) {
+                rel_op_node.add_child(Node::new(token_type, token_value));
+                self.tokens.consume_token();
+            }
+        }
+
+        father_node.add_child(rel_op_node);
+    }
 }
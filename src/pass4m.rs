@@ -0,0 +1,149 @@
+use crate::ir4m::{Function, Program};
+use crate::node4m::Node;
+use crate::opt4m;
+use std::time::Instant;
+
+#[derive(Clone, Copy)]
+pub enum Pass {
+    // Runs on the AST before it is lowered to three-address code
+    Ast(&'static str, fn(&mut Node)),
+    // Runs on every function of the three-address code
+    Tac(&'static str, fn(&mut Function)),
+}
+
+impl Pass {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pass::Ast(name, _) | Pass::Tac(name, _) => name,
+        }
+    }
+}
+
+const PASSES: &[Pass] = &[
+    Pass::Ast("fold", opt4m::fold),
+    Pass::Tac("cse", opt4m::cse),
+    Pass::Tac("dse", opt4m::dse),
+    Pass::Tac("dce", opt4m::dce),
+];
+
+pub fn pipeline(opt_level: u8) -> Vec<String> {
+    let names: &[&str] = match opt_level {
+        0 => &[],
+        1 => &["cse", "dse", "dce"],
+        _ => &["fold", "cse", "dse", "dce"],
+    };
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+pub struct PassManager {
+    passes: Vec<Pass>,
+    print_before: Vec<String>,
+    print_after: Vec<String>,
+    verbose: bool,
+}
+
+impl PassManager {
+    pub fn new(names: &[String]) -> Result<Self, String> {
+        let passes = names
+            .iter()
+            .map(|name| Self::lookup(name))
+            .collect::<Result<_, _>>()?;
+
+        Ok(PassManager {
+            passes,
+            print_before: Vec::new(),
+            print_after: Vec::new(),
+            verbose: false,
+        })
+    }
+
+    fn lookup(name: &str) -> Result<Pass, String> {
+        match PASSES.iter().find(|pass| pass.name() == name) {
+            Some(pass) => Ok(*pass),
+            None => {
+                let known: Vec<&str> = PASSES.iter().map(Pass::name).collect();
+                Err(format!(
+                    "unknown pass `{}` (available: {})",
+                    name,
+                    known.join(", ")
+                ))
+            }
+        }
+    }
+
+    // Names of registered passes, or "all"
+    fn check_print_names(names: &[String]) -> Result<(), String> {
+        for name in names.iter().filter(|name| *name != "all") {
+            Self::lookup(name)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_print_before(&mut self, names: Vec<String>) -> Result<(), String> {
+        Self::check_print_names(&names)?;
+        self.print_before = names;
+        Ok(())
+    }
+
+    pub fn set_print_after(&mut self, names: Vec<String>) -> Result<(), String> {
+        Self::check_print_names(&names)?;
+        self.print_after = names;
+        Ok(())
+    }
+
+    pub fn set_verbose(&mut self, verbose: bool) {
+        self.verbose = verbose;
+    }
+
+    fn should_print(names: &[String], pass: &Pass) -> bool {
+        names
+            .iter()
+            .any(|name| name == "all" || name == pass.name())
+    }
+
+    // AST passes run before lowering, in the order they were requested
+    pub fn run_ast(&self, ast: &mut Node) {
+        for pass in &self.passes {
+            if let Pass::Ast(name, run) = pass {
+                if Self::should_print(&self.print_before, pass) {
+                    eprintln!("*** AST before {} ***\n{}", name, ast.to_text());
+                }
+
+                let start = Instant::now();
+                run(ast);
+                self.report_time(name, start);
+
+                if Self::should_print(&self.print_after, pass) {
+                    eprintln!("*** AST after {} ***\n{}", name, ast.to_text());
+                }
+            }
+        }
+    }
+
+    // TAC passes run after lowering, in the order they were requested
+    pub fn run_tac(&self, program: &mut Program) {
+        for pass in &self.passes {
+            if let Pass::Tac(name, run) = pass {
+                if Self::should_print(&self.print_before, pass) {
+                    eprintln!("*** TAC before {} ***\n{}", name, program);
+                }
+
+                let start = Instant::now();
+                for function in &mut program.functions {
+                    run(function);
+                }
+                self.report_time(name, start);
+
+                if Self::should_print(&self.print_after, pass) {
+                    eprintln!("*** TAC after {} ***\n{}", name, program);
+                }
+            }
+        }
+    }
+
+    fn report_time(&self, name: &str, start: Instant) {
+        if self.verbose {
+            eprintln!("pass {:<8} {:>12.3?}", name, start.elapsed());
+        }
+    }
+}
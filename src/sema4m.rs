@@ -0,0 +1,402 @@
+use crate::diag4m::Diag4m;
+use crate::lex4m;
+use crate::mlir4m;
+use crate::node4m::Node;
+use std::collections::HashMap;
+
+// Builtin functions and their number of arguments. A subprogram of the same
+// name hides the builtin.
+const BUILTINS: &[(&str, usize)] = &[
+    ("ABS", 1),
+    ("MIN", 2),
+    ("MAX", 2),
+    ("ODD", 1),
+    ("ISQRT", 1),
+    ("RANDOM", 1),
+];
+
+// Builtin names are case insensitive like keywords
+pub fn builtin_arity(name: &str) -> Option<usize> {
+    let name = name.to_uppercase();
+    BUILTINS
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, arity)| *arity)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Program,
+    Function,
+    Procedure,
+}
+
+pub struct Sema4m<'a> {
+    diag: &'a mut Diag4m,
+    // Control variables of the enclosing FOR loops
+    loop_vars: Vec<String>,
+    // Number of loops enclosing the statement being checked
+    loop_depth: usize,
+    // Constants declared in the body being checked
+    constants: Vec<String>,
+    // Kind and number of parameters of every subprogram
+    subprograms: HashMap<String, (Kind, usize)>,
+    // Kind of the body being checked
+    kind: Kind,
+}
+
+impl<'a> Sema4m<'a> {
+    pub fn new(diag: &'a mut Diag4m) -> Self {
+        Sema4m {
+            diag,
+            loop_vars: Vec::new(),
+            loop_depth: 0,
+            constants: Vec::new(),
+            subprograms: HashMap::new(),
+            kind: Kind::Program,
+        }
+    }
+
+    pub fn check(&mut self, ast: &Node) {
+        // Collect every signature and global constant first so that calls
+        // may precede definitions
+        for child in ast.children() {
+            match child.name().as_str() {
+                "FUNCTION" | "PROCEDURE" | "EXTERN" => self.declare_subprogram(child),
+                "CONST" => self.check_const(child),
+                // Imported subprograms and constants
+                "IMPORT" => {
+                    for item in child.children() {
+                        if item.name() == "CONST" {
+                            self.check_const(item);
+                        } else if item.name() != "TYPEDEF" {
+                            self.declare_subprogram(item);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        let globals = self.constants.clone();
+
+        for child in ast.children() {
+            self.constants = globals.clone();
+            // Parameters hide global constants
+            if child.name() == "FUNCTION" || child.name() == "PROCEDURE" {
+                let params = child.children()[0].children();
+                self.constants
+                    .retain(|name| params.iter().all(|param| param.value() != name));
+            }
+            match child.name().as_str() {
+                "FUNCTION" => {
+                    self.kind = Kind::Function;
+                    self.check_statements(&child.children()[1]);
+                    if !Self::always_returns(&child.children()[1]) {
+                        self.diag.warning(
+                            child.loc(),
+                            format!("function `{}` might not return a value", child.value()),
+                        );
+                    }
+                }
+                "PROCEDURE" => {
+                    self.kind = Kind::Procedure;
+                    self.check_statements(&child.children()[1]);
+                }
+                "EXTERN" | "IMPORT" | "CONST" | "MODULE" | "TYPEDEF" => {}
+                _ => {
+                    self.kind = Kind::Program;
+                    self.check_statements(child);
+                }
+            }
+        }
+    }
+
+    fn declare_subprogram(&mut self, child: &Node) {
+        let kind = match child.name().as_str() {
+            "FUNCTION" => Kind::Function,
+            "PROCEDURE" => Kind::Procedure,
+            // An EXTERN function has a result TYPE after its parameters
+            "EXTERN" if child.children().len() == 2 => Kind::Function,
+            _ => Kind::Procedure,
+        };
+        if child.name() == "EXTERN" && mlir4m::is_runtime(child.value()) {
+            self.diag.error(
+                child.loc(),
+                format!("EXTERN `{}` clashes with a runtime function", child.value()),
+            );
+        }
+        let arity = child.children()[0].children().len();
+        if self.subprograms.contains_key(child.value()) {
+            self.diag.error(
+                child.loc(),
+                format!("`{}` is already defined", child.value()),
+            );
+        } else {
+            self.subprograms
+                .insert(child.value().to_string(), (kind, arity));
+        }
+    }
+
+    // CONST children: a CONSTANT named after each constant
+    fn check_const(&mut self, node: &Node) {
+        for constant in node.children() {
+            self.check_expression(&constant.children()[0]);
+            self.constants.push(constant.value().to_string());
+        }
+    }
+
+    fn check_statements(&mut self, node: &Node) {
+        for child in node.children() {
+            let children = child.children();
+            match child.name().as_str() {
+                "ASSIGNOP" | "COMPOUND" => {
+                    self.check_target(&children[0], "assign to");
+                    self.check_expression(&children[0]);
+                    self.check_expression(&children[1]);
+                }
+                "PARALLEL" => self.check_parallel(child),
+                "CONST" => self.check_const(child),
+                "READ" => {
+                    for id in children {
+                        self.check_target(id, "read into");
+                        self.check_expression(id);
+                    }
+                }
+                "WRITE" => {
+                    for expr in children {
+                        if expr.name() == "STRINGLITERAL" {
+                            self.check_string(expr);
+                        } else {
+                            self.check_expression(expr);
+                        }
+                    }
+                }
+                "IF" => {
+                    self.check_expression(&children[0]);
+                    for stmts in &children[1..] {
+                        self.check_statements(stmts);
+                    }
+                }
+                "WHILE" => {
+                    self.check_expression(&children[0]);
+                    self.loop_depth += 1;
+                    self.check_statements(&children[1]);
+                    self.loop_depth -= 1;
+                }
+                "REPEAT" => {
+                    self.loop_depth += 1;
+                    self.check_statements(&children[0]);
+                    self.loop_depth -= 1;
+                    self.check_expression(&children[1]);
+                }
+                "BREAK" | "CONTINUE" => {
+                    if self.loop_depth == 0 {
+                        self.diag
+                            .error(child.loc(), format!("{} outside of a loop", child.name()));
+                    }
+                }
+                "EXIT" => self.check_expression(&children[0]),
+                "ASSERT" => {
+                    self.check_expression(&children[0]);
+                    if let Some(message) = children.get(1) {
+                        self.check_string(message);
+                    }
+                }
+                "FOR" => self.check_for(child),
+                "CASE" => self.check_case(child),
+                "RETURN" => self.check_return(child),
+                "CALL" => self.check_call(child, false),
+                _ => self.check_statements(child),
+            }
+        }
+    }
+
+    fn check_expression(&mut self, node: &Node) {
+        if node.name() == "CALL" {
+            self.check_call(node, true);
+        } else {
+            for child in node.children() {
+                self.check_expression(child);
+            }
+        }
+    }
+
+    fn check_string(&mut self, node: &Node) {
+        if let Err(escape) = lex4m::unescape(node.value()) {
+            self.diag.error(
+                node.loc(),
+                format!("unknown escape sequence `{}` in string literal", escape),
+            );
+        }
+    }
+
+    fn check_call(&mut self, node: &Node, as_expression: bool) {
+        let name = node.value();
+        let signature = match self.subprograms.get(name) {
+            Some(&signature) => Some(signature),
+            None => builtin_arity(name).map(|arity| (Kind::Function, arity)),
+        };
+        match signature {
+            None => self
+                .diag
+                .error(node.loc(), format!("undefined function `{}`", name)),
+            Some((kind, arity)) => {
+                if as_expression && kind == Kind::Procedure {
+                    self.diag.error(
+                        node.loc(),
+                        format!("procedure `{}` does not return a value", name),
+                    );
+                }
+                let supplied = node.children().len();
+                if supplied != arity {
+                    self.diag.error(
+                        node.loc(),
+                        format!(
+                            "`{}` takes {} argument(s) but {} were supplied",
+                            name, arity, supplied
+                        ),
+                    );
+                }
+            }
+        }
+
+        for arg in node.children() {
+            self.check_expression(arg);
+        }
+    }
+
+    fn check_return(&mut self, node: &Node) {
+        let value = node.children().first();
+        match (self.kind, value) {
+            (Kind::Function, None) => self.diag.error(
+                node.loc(),
+                "RETURN in a function must have a value".to_string(),
+            ),
+            (Kind::Function, Some(value)) => self.check_expression(value),
+            (Kind::Procedure, Some(_)) => self.diag.error(
+                node.loc(),
+                "RETURN in a procedure cannot have a value".to_string(),
+            ),
+            (Kind::Program, Some(_)) => self.diag.error(
+                node.loc(),
+                "RETURN in the main program cannot have a value".to_string(),
+            ),
+            _ => {}
+        }
+    }
+
+    // A statement list always returns if one of its statements does: a
+    // RETURN or EXIT, an IF whose branches both return, or a REPEAT whose
+    // body does
+    fn always_returns(node: &Node) -> bool {
+        node.children().iter().any(|child| {
+            let children = child.children();
+            match child.name().as_str() {
+                "RETURN" | "EXIT" => true,
+                "IF" => children.len() > 2 && children[1..].iter().all(Self::always_returns),
+                "REPEAT" => Self::always_returns(&children[0]),
+                // Only a CASE with an ELSE covers every value
+                "CASE" => {
+                    children.last().is_some_and(|last| last.name() != "ARM")
+                        && children[1..].iter().all(|arm| match arm.name().as_str() {
+                            "ARM" => Self::always_returns(arm.children().last().unwrap()),
+                            _ => Self::always_returns(arm),
+                        })
+                }
+                _ => false,
+            }
+        })
+    }
+
+    fn check_target(&mut self, id: &Node, action: &str) {
+        if id.name() == "ID" && self.constants.contains(id.value()) {
+            self.diag.error(
+                id.loc(),
+                format!("cannot {} constant `{}`", action, id.value()),
+            );
+        } else if id.name() == "ID" && self.loop_vars.contains(id.value()) {
+            self.diag.error(
+                id.loc(),
+                format!(
+                    "cannot {} loop variable `{}` inside its FOR loop",
+                    action,
+                    id.value()
+                ),
+            );
+        }
+    }
+
+    // PARALLEL children: the list of targets, the list of values
+    fn check_parallel(&mut self, node: &Node) {
+        let targets = node.children()[0].children();
+        let values = node.children()[1].children();
+        if targets.len() != values.len() {
+            self.diag.error(
+                node.loc(),
+                format!(
+                    "assignment has {} target(s) but {} value(s)",
+                    targets.len(),
+                    values.len()
+                ),
+            );
+        }
+
+        let mut assigned: Vec<&str> = Vec::new();
+        for target in targets {
+            if target.name() == "ID" {
+                if assigned.contains(&target.value().as_str()) {
+                    self.diag.error(
+                        target.loc(),
+                        format!("`{}` is assigned more than once", target.value()),
+                    );
+                }
+                assigned.push(target.value());
+            }
+            self.check_target(target, "assign to");
+            self.check_expression(target);
+        }
+        for value in values {
+            self.check_expression(value);
+        }
+    }
+
+    // CASE children: selector, ARMs, then the ELSE statement list if any.
+    // ARM children: labels, then the statement list. Labels are checked by
+    // Type4m once constants are replaced.
+    fn check_case(&mut self, node: &Node) {
+        let children = node.children();
+        self.check_expression(&children[0]);
+
+        for arm in &children[1..] {
+            if arm.name() != "ARM" {
+                self.check_statements(arm);
+                continue;
+            }
+            self.check_statements(arm.children().last().unwrap());
+        }
+    }
+
+    // FOR children: ID, start, limit, step, body
+    fn check_for(&mut self, node: &Node) {
+        let children = node.children();
+        let id = &children[0];
+        if self.constants.contains(id.value()) {
+            self.diag.error(
+                id.loc(),
+                format!("FOR loop variable `{}` is a constant", id.value()),
+            );
+        } else {
+            self.check_target(id, "reuse");
+        }
+        // The STEP is checked by Type4m once constants are replaced
+        for expr in &children[1..4] {
+            self.check_expression(expr);
+        }
+
+        self.loop_vars.push(id.value().to_string());
+        self.loop_depth += 1;
+        self.check_statements(&children[4]);
+        self.loop_depth -= 1;
+        self.loop_vars.pop();
+    }
+}
@@ -0,0 +1,981 @@
+use crate::diag4m::Loc;
+use crate::ir4m::{BinOp, BlockId, Extern, Function, Inst, Op, Program, Reg, Terminator};
+use crate::lex4m;
+use crate::node4m::Node;
+use crate::type4m::Ty;
+use std::collections::{HashMap, HashSet};
+
+pub struct Tac4m<'a> {
+    ast: &'a Node,
+    function: Function,
+    current_block: BlockId,
+    current_loc: Loc,
+    var_map: HashMap<String, Reg>,
+    // Whether array accesses check their index at run time
+    bounds_check: bool,
+    // Source file named in assertion failures, None to drop assertions
+    asserts: Option<String>,
+    // CONTINUE and BREAK targets of the enclosing loops
+    loops: Vec<(BlockId, BlockId)>,
+    // Seed of RANDOM, None to seed it from the system
+    seed: Option<i64>,
+    // Functions declared with EXTERN or imported from other modules
+    externs: Vec<Extern>,
+    // Symbol of every EXTERN and imported function, by the name it is
+    // called by
+    symbols: HashMap<String, String>,
+    // Name of the MODULE being compiled, None for a program
+    module: Option<String>,
+    // Names the MODULE exports; its other subprograms are private
+    exports: HashSet<String>,
+}
+
+impl<'a> Tac4m<'a> {
+    pub fn new(ast: &'a Node) -> Self {
+        let function = Function::new("main".to_string());
+        let current_block = function.entry();
+        Self {
+            ast,
+            function,
+            current_block,
+            current_loc: Loc::default(),
+            var_map: HashMap::new(),
+            bounds_check: false,
+            asserts: None,
+            loops: Vec::new(),
+            seed: None,
+            externs: Vec::new(),
+            symbols: HashMap::new(),
+            module: None,
+            exports: HashSet::new(),
+        }
+    }
+
+    pub fn set_bounds_check(&mut self, bounds_check: bool) {
+        self.bounds_check = bounds_check;
+    }
+
+    pub fn set_asserts(&mut self, source_file: Option<String>) {
+        self.asserts = source_file;
+    }
+
+    pub fn set_seed(&mut self, seed: Option<i64>) {
+        self.seed = seed;
+    }
+
+    pub fn generate_tac(&mut self) -> Program {
+        for child in self.ast.children() {
+            match child.name().as_str() {
+                "MODULE" => {
+                    self.module = Some(child.value().to_string());
+                    self.exports = child
+                        .children()
+                        .iter()
+                        .map(|id| id.value().to_string())
+                        .collect();
+                }
+                "EXTERN" => self.declare_extern(child),
+                "IMPORT" => self.declare_import(child),
+                _ => {}
+            }
+        }
+
+        let mut functions = Vec::new();
+        for child in self.ast.children() {
+            match child.name().as_str() {
+                "FUNCTION" | "PROCEDURE" => functions.push(self.lower_subprogram(child)),
+                // A module has no main program
+                "EXTERN" | "IMPORT" | "CONST" | "MODULE" | "TYPEDEF" => {}
+                // The program's exit status is 0 unless it calls EXIT
+                _ => {
+                    self.begin_function("main".to_string(), Some(Ty::Integer));
+                    self.lower_seed();
+                    self.lower_statements(child);
+                    let value = self.default_return();
+                    self.terminate(Terminator::Return(value));
+                    functions.push(self.finish_function());
+                }
+            }
+        }
+
+        Program {
+            externs: std::mem::take(&mut self.externs),
+            functions,
+        }
+    }
+
+    // EXTERN children: parameter list of TYPEs, then the result TYPE if any
+    fn declare_extern(&mut self, node: &Node) {
+        let params = node.children()[0]
+            .children()
+            .iter()
+            .map(Self::expression_ty)
+            .collect();
+        self.externs.push(Extern {
+            name: node.value().to_string(),
+            params,
+            ret: node.ty(),
+        });
+        self.symbols
+            .insert(node.value().to_string(), node.value().to_string());
+    }
+
+    // IMPORT children: the module's exported TYPEDEFs and FUNCTION and
+    // PROCEDURE headers, then a CONST whose values were already substituted
+    fn declare_import(&mut self, node: &Node) {
+        for item in node.children() {
+            if item.name() == "CONST" || item.name() == "TYPEDEF" {
+                continue;
+            }
+            let name = Self::symbol(Some(node.value()), item.value());
+            let params = item.children()[0]
+                .children()
+                .iter()
+                .map(Self::expression_ty)
+                .collect();
+            self.externs.push(Extern {
+                name: name.clone(),
+                params,
+                ret: item.ty(),
+            });
+            self.symbols.insert(item.value().to_string(), name);
+        }
+    }
+
+    // User subprograms are prefixed so they cannot clash with `main` or the
+    // runtime entry points, and subprograms of a module are qualified with
+    // its name so that separately compiled modules link together
+    fn symbol(module: Option<&str>, name: &str) -> String {
+        match module {
+            Some(module) => format!("micro_{}.{}", module, name),
+            None => format!("micro_{}", name),
+        }
+    }
+
+    fn begin_function(&mut self, name: String, ret: Option<Ty>) {
+        self.function = Function::new(name);
+        self.function.ret = ret;
+        self.current_block = self.function.entry();
+        self.current_loc = Loc::default();
+        self.var_map.clear();
+        self.loops.clear();
+    }
+
+    fn finish_function(&mut self) -> Function {
+        std::mem::replace(&mut self.function, Function::new(String::new()))
+    }
+
+    // FUNCTION/PROCEDURE children: parameter list, body. A function that
+    // falls off the end returns 0.
+    fn lower_subprogram(&mut self, node: &Node) -> Function {
+        let ret = node.ty();
+        let name = Self::symbol(self.module.as_deref(), node.value());
+        self.begin_function(name, ret);
+
+        let children = node.children();
+        for param in children[0].children() {
+            let reg = self.var(param);
+            self.function.params.push(reg);
+        }
+
+        self.lower_statements(&children[1]);
+        self.current_loc = node.loc();
+        let value = self.default_return();
+        self.terminate(Terminator::Return(value));
+        let mut function = self.finish_function();
+        function.private = !self.exports.contains(node.value());
+        function
+    }
+
+    // The program seeds RANDOM before its first statement
+    fn lower_seed(&mut self) {
+        if let Some(value) = self.seed {
+            let seed = self.temp(Ty::Long);
+            self.emit(Op::Const { dst: seed, value });
+            self.emit(Op::Call {
+                dst: None,
+                callee: "random_seed".to_string(),
+                args: vec![seed],
+            });
+        }
+    }
+
+    // Value returned without a RETURN value: 0, or nothing for procedures
+    fn default_return(&mut self) -> Option<Reg> {
+        self.function.ret.map(|ty| {
+            let dst = self.temp(ty);
+            self.emit(Op::Const { dst, value: 0 });
+            dst
+        })
+    }
+
+    fn emit(&mut self, op: Op) {
+        let block = self.current_block;
+        let loc = self.current_loc;
+        self.function.block_mut(block).insts.push(Inst { op, loc });
+    }
+
+    fn terminate(&mut self, terminator: Terminator) {
+        let block = self.current_block;
+        self.function.block_mut(block).terminator = terminator;
+    }
+
+    fn var(&mut self, id: &Node) -> Reg {
+        if let Some(reg) = self.var_map.get(id.value()) {
+            return *reg;
+        }
+        let reg = self
+            .function
+            .new_reg(Some(id.value().to_string()), Self::expression_ty(id));
+        self.var_map.insert(id.value().to_string(), reg);
+        reg
+    }
+
+    fn temp(&mut self, ty: Ty) -> Reg {
+        self.function.new_reg(None, ty)
+    }
+
+    fn binary_op(name: &str) -> Option<BinOp> {
+        match name {
+            "PLUSOP" => Some(BinOp::Add),
+            "MINUSOP" => Some(BinOp::Sub),
+            "MULTIOP" => Some(BinOp::Mul),
+            "DIVIDEOP" => Some(BinOp::Div),
+            "MOD" => Some(BinOp::Rem),
+            "POWOP" => Some(BinOp::Pow),
+            "AND" => Some(BinOp::And),
+            "OR" => Some(BinOp::Or),
+            "XOR" => Some(BinOp::Xor),
+            "SHL" => Some(BinOp::Shl),
+            "SHR" => Some(BinOp::Shr),
+            "LTOP" => Some(BinOp::Lt),
+            "LEOP" => Some(BinOp::Le),
+            "GTOP" => Some(BinOp::Gt),
+            "GEOP" => Some(BinOp::Ge),
+            "EQOP" => Some(BinOp::Eq),
+            "NEOP" => Some(BinOp::Ne),
+            _ => None,
+        }
+    }
+
+    // Type the type checker annotated the expression with
+    fn expression_ty(node: &Node) -> Ty {
+        node.ty()
+            .unwrap_or_else(|| panic!("Untyped expression node: {}", node))
+    }
+
+    fn lower_statements(&mut self, node: &Node) {
+        for child in node.children() {
+            self.current_loc = child.loc();
+            match child.name().as_str() {
+                "ASSIGNOP" => self.lower_assign(child),
+                "COMPOUND" => self.lower_compound(child),
+                "PARALLEL" => self.lower_parallel(child),
+                "READ" => self.lower_read(child),
+                "WRITE" => self.lower_write(child),
+                "IF" => self.lower_if(child),
+                "WHILE" => self.lower_while(child),
+                "FOR" => self.lower_for(child),
+                "REPEAT" => self.lower_repeat(child),
+                "CASE" => self.lower_case(child),
+                "RETURN" => self.lower_return(child),
+                "BREAK" => self.lower_jump(|(_, break_block)| break_block),
+                "CONTINUE" => self.lower_jump(|(continue_block, _)| continue_block),
+                "EXIT" => self.lower_exit(child),
+                "ASSERT" => self.lower_assert(child),
+                "CALL" => {
+                    let dst = child.ty().map(|ty| self.temp(ty));
+                    self.lower_call(child, dst);
+                }
+                "BUILTIN" => {
+                    self.lower_expression(child);
+                }
+                "DECLARE" => self.lower_declare(child),
+                // Uses of constants were replaced by their values
+                "CONST" => {}
+                _ => self.lower_statements(child),
+            }
+        }
+    }
+
+    // Arrays and records are allocated where they are declared
+    fn lower_declare(&mut self, node: &Node) {
+        for id in node.children() {
+            if id.name() == "ID" && !Self::expression_ty(id).is_scalar() {
+                let dst = self.var(id);
+                self.emit(Op::Alloca { dst });
+            }
+        }
+    }
+
+    fn lower_assign(&mut self, node: &Node) {
+        let target = &node.children()[0];
+        if target.name() != "ID" {
+            let src = self.lower_expression(&node.children()[1]);
+            self.lower_store(target, src);
+        } else {
+            let dst = self.var(target);
+            self.lower_expression_into(&node.children()[1], dst);
+        }
+    }
+
+    // COMPOUND children: the target, then the operation applied to it, whose
+    // left operand is a copy of the target. An array element or a record
+    // field is located once, and both read and written through that.
+    fn lower_compound(&mut self, node: &Node) {
+        let target = &node.children()[0];
+        if target.name() == "ID" {
+            self.lower_assign(node);
+            return;
+        }
+
+        let op_node = &node.children()[1];
+        let op = Self::binary_op(op_node.name())
+            .unwrap_or_else(|| panic!("Unexpected compound operation: {}", op_node));
+        let current = self.temp(Self::expression_ty(target));
+        let result = self.temp(Self::expression_ty(op_node));
+        let store = if target.name() == "FIELD" {
+            let (record, field) = self.lower_field(target);
+            self.emit(Op::LoadField {
+                dst: current,
+                record,
+                field,
+            });
+            Op::StoreField {
+                record,
+                field,
+                src: result,
+            }
+        } else {
+            let (array, index) = self.lower_index(target);
+            self.emit(Op::Load {
+                dst: current,
+                array,
+                index,
+            });
+            Op::Store {
+                array,
+                index,
+                src: result,
+            }
+        };
+        let rhs = self.lower_expression(&op_node.children()[1]);
+        self.emit(Op::Binary {
+            dst: result,
+            op,
+            lhs: current,
+            rhs,
+        });
+        self.emit(store);
+    }
+
+    // PARALLEL children: the list of targets, the list of values. Every value
+    // is computed into a temporary before any target is written.
+    fn lower_parallel(&mut self, node: &Node) {
+        let targets = node.children()[0].children();
+        let values: Vec<Reg> = node.children()[1]
+            .children()
+            .iter()
+            .map(|value| {
+                let dst = self.temp(Self::expression_ty(value));
+                self.lower_expression_into(value, dst);
+                dst
+            })
+            .collect();
+
+        for (target, src) in targets.iter().zip(values) {
+            if target.name() != "ID" {
+                self.lower_store(target, src);
+            } else {
+                let dst = self.var(target);
+                self.emit(Op::Copy { dst, src });
+            }
+        }
+    }
+
+    fn lower_read(&mut self, node: &Node) {
+        for child in node.children() {
+            self.current_loc = child.loc();
+            if child.name() != "ID" {
+                let dst = self.temp(Self::expression_ty(child));
+                self.emit(Op::Read { dst });
+                self.lower_store(child, dst);
+            } else {
+                let dst = self.var(child);
+                self.emit(Op::Read { dst });
+            }
+        }
+    }
+
+    // Array and index register of `a[i]`, checking the index if asked to
+    fn lower_index(&mut self, node: &Node) -> (Reg, Reg) {
+        let array = self.var_map[node.value()];
+        let index = self.lower_expression(&node.children()[0]);
+        if self.bounds_check
+            && let Ty::Array(_, len) = self.function.reg_ty(array)
+        {
+            self.emit(Op::CheckBounds { index, len });
+        }
+        (array, index)
+    }
+
+    // Record register and field number of `r.f`
+    fn lower_field(&mut self, node: &Node) -> (Reg, usize) {
+        let record = self.var_map[node.children()[0].value()];
+        let Ty::Record(fields) = self.function.reg_ty(record) else {
+            panic!("Not a record: {}", node);
+        };
+        let (field, _) = fields.field(node.value()).expect("Unknown field");
+        (record, field)
+    }
+
+    // Store into an array element or a record field
+    fn lower_store(&mut self, node: &Node, src: Reg) {
+        if node.name() == "FIELD" {
+            let (record, field) = self.lower_field(node);
+            self.emit(Op::StoreField { record, field, src });
+        } else {
+            let (array, index) = self.lower_index(node);
+            self.emit(Op::Store { array, index, src });
+        }
+    }
+
+    // Every value of a WRITE is printed on a line of its own; strings are
+    // printed as they are, so that they can label the value after them. A
+    // WRITE that ends with a string ends its line, like any other WRITE
+    fn lower_write(&mut self, node: &Node) {
+        for child in node.children() {
+            if child.name() == "STRINGLITERAL" {
+                let text = lex4m::unescape(child.value()).expect("Invalid string literal");
+                self.emit(Op::PrintStr { text });
+            } else {
+                let src = self.lower_expression(child);
+                self.emit(Op::Print { src });
+            }
+        }
+
+        if node
+            .children()
+            .last()
+            .is_some_and(|child| child.name() == "STRINGLITERAL")
+        {
+            self.emit(Op::PrintStr {
+                text: "\n".to_string(),
+            });
+        }
+    }
+
+    fn lower_if(&mut self, node: &Node) {
+        let children = node.children();
+        let cond = self.lower_expression(&children[0]);
+
+        let then_block = self.function.new_block();
+        let else_block = if children.len() > 2 {
+            Some(self.function.new_block())
+        } else {
+            None
+        };
+        let join_block = self.function.new_block();
+
+        self.terminate(Terminator::Branch {
+            cond,
+            then_block,
+            else_block: else_block.unwrap_or(join_block),
+        });
+
+        self.current_block = then_block;
+        self.lower_statements(&children[1]);
+        self.terminate(Terminator::Jump(join_block));
+
+        if let Some(else_block) = else_block {
+            self.current_block = else_block;
+            self.lower_statements(&children[2]);
+            self.terminate(Terminator::Jump(join_block));
+        }
+
+        self.current_block = join_block;
+    }
+
+    // The condition is evaluated in its own header block so that variables
+    // reassigned in the body flow back into it as block arguments
+    fn lower_while(&mut self, node: &Node) {
+        let children = node.children();
+
+        let header_block = self.function.new_block();
+        let body_block = self.function.new_block();
+        let exit_block = self.function.new_block();
+
+        self.terminate(Terminator::Jump(header_block));
+
+        self.current_block = header_block;
+        let cond = self.lower_expression(&children[0]);
+        self.terminate(Terminator::Branch {
+            cond,
+            then_block: body_block,
+            else_block: exit_block,
+        });
+
+        self.current_block = body_block;
+        self.loops.push((header_block, exit_block));
+        self.lower_statements(&children[1]);
+        self.loops.pop();
+        self.terminate(Terminator::Jump(header_block));
+
+        self.current_block = exit_block;
+    }
+
+    // FOR children: ID, start, limit, step, body. The limit is evaluated once
+    // and the step is a nonzero constant, so its sign picks the comparison.
+    // CONTINUE jumps to the increment, which has a block of its own.
+    fn lower_for(&mut self, node: &Node) {
+        let children = node.children();
+        let var = self.var(&children[0]);
+        let ty = self.function.reg_ty(var);
+        // The limit may refer to the loop variable, so it is only assigned
+        // once both bounds are computed
+        let start = self.temp(ty);
+        self.lower_expression_into(&children[1], start);
+        let limit = self.temp(ty);
+        self.lower_expression_into(&children[2], limit);
+        self.emit(Op::Copy {
+            dst: var,
+            src: start,
+        });
+        let step: i64 = children[3].value().parse().expect("Invalid FOR step");
+
+        let header_block = self.function.new_block();
+        let body_block = self.function.new_block();
+        let step_block = self.function.new_block();
+        let exit_block = self.function.new_block();
+
+        self.terminate(Terminator::Jump(header_block));
+
+        self.current_block = header_block;
+        let cond = self.temp(Ty::Boolean);
+        let op = if step > 0 { BinOp::Le } else { BinOp::Ge };
+        self.emit(Op::Binary {
+            dst: cond,
+            op,
+            lhs: var,
+            rhs: limit,
+        });
+        self.terminate(Terminator::Branch {
+            cond,
+            then_block: body_block,
+            else_block: exit_block,
+        });
+
+        self.current_block = body_block;
+        self.loops.push((step_block, exit_block));
+        self.lower_statements(&children[4]);
+        self.loops.pop();
+        self.terminate(Terminator::Jump(step_block));
+
+        self.current_block = step_block;
+        self.current_loc = node.loc();
+        let step_reg = self.temp(ty);
+        self.emit(Op::Const {
+            dst: step_reg,
+            value: step,
+        });
+        self.emit(Op::Binary {
+            dst: var,
+            op: BinOp::Add,
+            lhs: var,
+            rhs: step_reg,
+        });
+        self.terminate(Terminator::Jump(header_block));
+
+        self.current_block = exit_block;
+    }
+
+    // REPEAT children: body, condition. The body always runs at least once,
+    // and CONTINUE jumps to the condition.
+    fn lower_repeat(&mut self, node: &Node) {
+        let children = node.children();
+
+        let body_block = self.function.new_block();
+        let cond_block = self.function.new_block();
+        let exit_block = self.function.new_block();
+
+        self.terminate(Terminator::Jump(body_block));
+
+        self.current_block = body_block;
+        self.loops.push((cond_block, exit_block));
+        self.lower_statements(&children[0]);
+        self.loops.pop();
+        self.terminate(Terminator::Jump(cond_block));
+
+        self.current_block = cond_block;
+        self.current_loc = node.loc();
+        let cond = self.lower_expression(&children[1]);
+        self.terminate(Terminator::Branch {
+            cond,
+            then_block: exit_block,
+            else_block: body_block,
+        });
+
+        self.current_block = exit_block;
+    }
+
+    // CASE children: selector, ARMs, then the ELSE statement list if any.
+    // Dense labels become a switch, sparse ones a chain of comparisons.
+    fn lower_case(&mut self, node: &Node) {
+        let children = node.children();
+        let selector = self.lower_expression(&children[0]);
+        let ty = self.function.reg_ty(selector);
+
+        let mut cases = Vec::new();
+        let mut arms = Vec::new();
+        let mut else_stmts = None;
+        for arm in &children[1..] {
+            if arm.name() != "ARM" {
+                else_stmts = Some(arm);
+                continue;
+            }
+            let block = self.function.new_block();
+            let (stmts, labels) = arm.children().split_last().unwrap();
+            for label in labels {
+                let value = label.value().parse().expect("Invalid CASE label");
+                cases.push((value, block));
+            }
+            arms.push((block, stmts));
+        }
+        let join_block = self.function.new_block();
+        let default = match else_stmts {
+            Some(_) => self.function.new_block(),
+            None => join_block,
+        };
+
+        if Self::is_dense(&cases) {
+            self.terminate(Terminator::Switch {
+                value: selector,
+                default,
+                cases,
+            });
+        } else {
+            for (value, block) in cases {
+                let label = self.temp(ty);
+                self.emit(Op::Const { dst: label, value });
+                let cond = self.temp(Ty::Boolean);
+                self.emit(Op::Binary {
+                    dst: cond,
+                    op: BinOp::Eq,
+                    lhs: selector,
+                    rhs: label,
+                });
+                let next_block = self.function.new_block();
+                self.terminate(Terminator::Branch {
+                    cond,
+                    then_block: block,
+                    else_block: next_block,
+                });
+                self.current_block = next_block;
+            }
+            self.terminate(Terminator::Jump(default));
+        }
+
+        for (block, stmts) in arms {
+            self.current_block = block;
+            self.lower_statements(stmts);
+            self.terminate(Terminator::Jump(join_block));
+        }
+        if let Some(stmts) = else_stmts {
+            self.current_block = default;
+            self.lower_statements(stmts);
+            self.terminate(Terminator::Jump(join_block));
+        }
+
+        self.current_block = join_block;
+    }
+
+    // Labels are dense when they fill at least half of the range they span
+    fn is_dense(cases: &[(i64, BlockId)]) -> bool {
+        let min = cases.iter().map(|(value, _)| *value).min();
+        let max = cases.iter().map(|(value, _)| *value).max();
+        match (min, max) {
+            (Some(min), Some(max)) => 2 * cases.len() as i128 > max as i128 - min as i128,
+            _ => false,
+        }
+    }
+
+    // Anything after a RETURN goes into a fresh block that dce removes as
+    // unreachable
+    fn lower_return(&mut self, node: &Node) {
+        let value = match node.children().first() {
+            Some(child) => Some(self.lower_expression(child)),
+            None => self.default_return(),
+        };
+        self.terminate(Terminator::Return(value));
+        self.current_block = self.function.new_block();
+    }
+
+    // BREAK and CONTINUE jump to a target of the innermost loop
+    fn lower_jump(&mut self, target: fn((BlockId, BlockId)) -> BlockId) {
+        let target = target(*self.loops.last().expect("Jump outside of a loop"));
+        self.terminate(Terminator::Jump(target));
+        self.current_block = self.function.new_block();
+    }
+
+    // ASSERT children: condition, then an optional message. The failure
+    // message with the source position is built at compile time.
+    fn lower_assert(&mut self, node: &Node) {
+        let Some(source_file) = &self.asserts else {
+            return;
+        };
+        let loc = node.loc();
+        let mut message = format!(
+            "assertion failed at {}:{}:{}",
+            source_file, loc.line, loc.col
+        );
+        if let Some(text) = node.children().get(1) {
+            let text = lex4m::unescape(text.value()).expect("Invalid string literal");
+            message = format!("{}: {}", message, text);
+        }
+        let cond = self.lower_expression(&node.children()[0]);
+        self.emit(Op::Assert { cond, message });
+    }
+
+    // EXIT calls the C library's exit with a 32-bit status; it does not return
+    fn lower_exit(&mut self, node: &Node) {
+        let mut status = self.lower_expression(&node.children()[0]);
+        if self.function.reg_ty(status) != Ty::Integer {
+            let dst = self.temp(Ty::Integer);
+            self.emit(Op::Convert { dst, src: status });
+            status = dst;
+        }
+        self.emit(Op::Call {
+            dst: None,
+            callee: "exit".to_string(),
+            args: vec![status],
+        });
+    }
+
+    fn lower_call(&mut self, node: &Node, dst: Option<Reg>) {
+        let args = node
+            .children()
+            .iter()
+            .map(|child| self.lower_expression(child))
+            .collect();
+        let callee = match self.symbols.get(node.value()) {
+            Some(symbol) => symbol.clone(),
+            None => Self::symbol(self.module.as_deref(), node.value()),
+        };
+        self.emit(Op::Call { dst, callee, args });
+    }
+
+    // ABS, MIN, MAX and ODD are lowered inline; ISQRT and RANDOM call the
+    // runtime. ABS(x) is MAX(x, 0 - x).
+    fn lower_builtin(&mut self, node: &Node, dst: Reg) {
+        let args: Vec<Reg> = node
+            .children()
+            .iter()
+            .map(|child| self.lower_expression(child))
+            .collect();
+        let ty = self.function.reg_ty(args[0]);
+        match node.value().as_str() {
+            "ABS" => {
+                let zero = self.temp(ty);
+                if ty == Ty::Real {
+                    self.emit(Op::ConstReal {
+                        dst: zero,
+                        value: 0.0,
+                    });
+                } else {
+                    self.emit(Op::Const {
+                        dst: zero,
+                        value: 0,
+                    });
+                }
+                let negated = self.temp(ty);
+                self.emit(Op::Binary {
+                    dst: negated,
+                    op: BinOp::Sub,
+                    lhs: zero,
+                    rhs: args[0],
+                });
+                self.emit(Op::Binary {
+                    dst,
+                    op: BinOp::Max,
+                    lhs: args[0],
+                    rhs: negated,
+                });
+            }
+            "MIN" | "MAX" => {
+                let op = if node.value() == "MIN" {
+                    BinOp::Min
+                } else {
+                    BinOp::Max
+                };
+                self.emit(Op::Binary {
+                    dst,
+                    op,
+                    lhs: args[0],
+                    rhs: args[1],
+                });
+            }
+            "ODD" => {
+                let one = self.temp(ty);
+                self.emit(Op::Const { dst: one, value: 1 });
+                let bit = self.temp(ty);
+                self.emit(Op::Binary {
+                    dst: bit,
+                    op: BinOp::And,
+                    lhs: args[0],
+                    rhs: one,
+                });
+                let zero = self.temp(ty);
+                self.emit(Op::Const {
+                    dst: zero,
+                    value: 0,
+                });
+                self.emit(Op::Binary {
+                    dst,
+                    op: BinOp::Ne,
+                    lhs: bit,
+                    rhs: zero,
+                });
+            }
+            name => {
+                let callee = match (name, ty) {
+                    ("ISQRT", Ty::Long) => "isqrt_long",
+                    ("ISQRT", _) => "isqrt",
+                    (_, Ty::Long) => "random_long",
+                    _ => "random_int",
+                };
+                self.emit(Op::Call {
+                    dst: Some(dst),
+                    callee: callee.to_string(),
+                    args,
+                });
+            }
+        }
+    }
+
+    // Lower an expression, returning the register that holds its value
+    fn lower_expression(&mut self, node: &Node) -> Reg {
+        match node.name().as_str() {
+            "ID" => self.var(node),
+            _ => {
+                let dst = self.temp(Self::expression_ty(node));
+                self.lower_expression_into(node, dst);
+                dst
+            }
+        }
+    }
+
+    // AND and OR short-circuit: the right operand is only evaluated when the
+    // left one does not decide the result. `dst` is written on the edges into
+    // the join block, after both operands have been read, since it may be
+    // one of them.
+    fn lower_logical(&mut self, node: &Node, dst: Reg) {
+        let children = node.children();
+        let lhs = self.lower_expression(&children[0]);
+
+        let rhs_block = self.function.new_block();
+        let short_block = self.function.new_block();
+        let join_block = self.function.new_block();
+        let (then_block, else_block) = if node.name() == "AND" {
+            (rhs_block, short_block)
+        } else {
+            (short_block, rhs_block)
+        };
+        self.terminate(Terminator::Branch {
+            cond: lhs,
+            then_block,
+            else_block,
+        });
+
+        self.current_block = short_block;
+        self.emit(Op::Copy { dst, src: lhs });
+        self.terminate(Terminator::Jump(join_block));
+
+        self.current_block = rhs_block;
+        let rhs = self.lower_expression(&children[1]);
+        self.emit(Op::Copy { dst, src: rhs });
+        self.terminate(Terminator::Jump(join_block));
+
+        self.current_block = join_block;
+    }
+
+    // Lower an expression, writing its value directly into `dst`
+    fn lower_expression_into(&mut self, node: &Node, dst: Reg) {
+        match node.name().as_str() {
+            "INTLITERAL" => {
+                let value = node.value().parse().expect("Invalid integer literal");
+                self.emit(Op::Const { dst, value });
+            }
+            "ID" => {
+                let src = self.var(node);
+                self.emit(Op::Copy { dst, src });
+            }
+            "REALLITERAL" => {
+                let value = node.value().parse().expect("Invalid real literal");
+                self.emit(Op::ConstReal { dst, value });
+            }
+            "BOOLLITERAL" => {
+                let value = (node.value() == "true") as i64;
+                self.emit(Op::Const { dst, value });
+            }
+            "CALL" => self.lower_call(node, Some(dst)),
+            "BUILTIN" => self.lower_builtin(node, dst),
+            "INDEX" => {
+                let (array, index) = self.lower_index(node);
+                self.emit(Op::Load { dst, array, index });
+            }
+            "FIELD" => {
+                let (record, field) = self.lower_field(node);
+                self.emit(Op::LoadField { dst, record, field });
+            }
+            "CONVERT" => {
+                let src = self.lower_expression(&node.children()[0]);
+                self.emit(Op::Convert { dst, src });
+            }
+            "AND" | "OR" if Self::expression_ty(node) == Ty::Boolean => {
+                self.lower_logical(node, dst)
+            }
+            "NOT" => {
+                let src = self.lower_expression(&node.children()[0]);
+                let mask = self.temp(Ty::Boolean);
+                self.emit(Op::Const {
+                    dst: mask,
+                    value: 1,
+                });
+                self.emit(Op::Binary {
+                    dst,
+                    op: BinOp::Xor,
+                    lhs: src,
+                    rhs: mask,
+                });
+            }
+            name => {
+                let op = Self::binary_op(name)
+                    .unwrap_or_else(|| panic!("Unexpected expression node: {}", node));
+                let lhs = self.lower_expression(&node.children()[0]);
+                let mut rhs = self.lower_expression(&node.children()[1]);
+                if matches!(op, BinOp::Shl | BinOp::Shr) {
+                    rhs = self.lower_shift_amount(rhs, Self::expression_ty(node));
+                }
+                self.emit(Op::Binary { dst, op, lhs, rhs });
+            }
+        }
+    }
+
+    // MLIR makes a shift by the width or more poison, so the amount is taken modulo the width
+    fn lower_shift_amount(&mut self, amount: Reg, ty: Ty) -> Reg {
+        let bits = if ty == Ty::Long { 64 } else { 32 };
+        let mask = self.temp(ty);
+        self.emit(Op::Const {
+            dst: mask,
+            value: bits - 1,
+        });
+        let masked = self.temp(ty);
+        self.emit(Op::Binary {
+            dst: masked,
+            op: BinOp::And,
+            lhs: amount,
+            rhs: mask,
+        });
+        masked
+    }
+}
@@ -1,12 +1,16 @@
+use crate::diag4m::Loc;
+
 pub struct Token4m {
     tokens: Vec<(String, String)>,
+    positions: Vec<Loc>,
     current_index: usize,
 }
 
 impl Token4m {
-    pub fn new(tokens: Vec<(String, String)>) -> Self {
+    pub fn new(tokens: Vec<(String, String)>, positions: Vec<Loc>) -> Self {
         Token4m {
             tokens,
+            positions,
             current_index: 0,
         }
     }
@@ -20,6 +24,18 @@ impl Token4m {
         }
     }
 
+    // Token `offset` positions after the next one, without consuming anything
+    pub fn peek_token(&self, offset: usize) -> Option<(String, String)> {
+        self.tokens.get(self.current_index + offset).cloned()
+    }
+
+    pub fn position(&self) -> Loc {
+        self.positions
+            .get(self.current_index)
+            .copied()
+            .unwrap_or_default()
+    }
+
     pub fn consume_token(&mut self) {
         self.current_index += 1;
     }
@@ -0,0 +1,1153 @@
+use crate::diag4m::{Diag4m, Loc};
+use crate::node4m::Node;
+use crate::sema4m;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Ty {
+    Integer,
+    Long,
+    Real,
+    Boolean,
+    // Element type and length
+    Array(&'static Ty, usize),
+    // Records are leaked once per TYPE declaration so that Ty stays Copy
+    Record(&'static Record),
+}
+
+// Named scalar fields, laid out in order
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Record {
+    pub name: String,
+    pub fields: Vec<(String, Ty)>,
+}
+
+impl Record {
+    // Position and type of a field
+    pub fn field(&self, name: &str) -> Option<(usize, Ty)> {
+        self.fields
+            .iter()
+            .position(|(field, _)| field == name)
+            .map(|i| (i, self.fields[i].1))
+    }
+
+    pub fn mlir_struct(&self) -> String {
+        let fields: Vec<String> = self.fields.iter().map(|(_, ty)| ty.mlir()).collect();
+        format!("!llvm.struct<({})>", fields.join(", "))
+    }
+}
+
+// Array element types, so that Ty::Array can refer to them statically
+static SCALARS: [Ty; 4] = [Ty::Integer, Ty::Long, Ty::Real, Ty::Boolean];
+
+impl Ty {
+    pub fn array(elem: Ty, len: usize) -> Ty {
+        let elem = SCALARS
+            .iter()
+            .find(|scalar| **scalar == elem)
+            .expect("Array elements must be scalars");
+        Ty::Array(elem, len)
+    }
+
+    pub fn record(record: Record) -> Ty {
+        Ty::Record(Box::leak(Box::new(record)))
+    }
+
+    pub fn mlir(&self) -> String {
+        match self {
+            Ty::Integer => "i32".to_string(),
+            Ty::Long => "i64".to_string(),
+            Ty::Real => "f64".to_string(),
+            Ty::Boolean => "i1".to_string(),
+            Ty::Array(elem, len) => format!("memref<{}x{}>", len, elem.mlir()),
+            // Records are always handled through a pointer to them
+            Ty::Record(_) => "!llvm.ptr".to_string(),
+        }
+    }
+
+    pub fn is_scalar(&self) -> bool {
+        !matches!(self, Ty::Array(..) | Ty::Record(_))
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Ty::Integer | Ty::Long)
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Ty::Integer | Ty::Long | Ty::Real)
+    }
+
+    // Whether a value of this type converts implicitly to `to`: integers
+    // widen to LONG, and any integer promotes to REAL
+    pub fn widens_to(&self, to: Ty) -> bool {
+        *self == to
+            || (*self == Ty::Integer && to == Ty::Long)
+            || (self.is_integer() && to == Ty::Real)
+    }
+
+    // Whether `value` fits in this integer type
+    pub fn fits(&self, value: i64) -> bool {
+        match self {
+            Ty::Integer => i32::try_from(value).is_ok(),
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ty::Integer => write!(f, "INTEGER"),
+            Ty::Long => write!(f, "LONG"),
+            Ty::Real => write!(f, "REAL"),
+            Ty::Boolean => write!(f, "BOOLEAN"),
+            Ty::Array(elem, len) => write!(f, "ARRAY[{}] OF {}", len, elem),
+            Ty::Record(record) => write!(f, "{}", record.name),
+        }
+    }
+}
+
+// Value of a constant, computed at compile time
+#[derive(Clone, Copy)]
+enum Value {
+    Int(i64),
+    Real(f64),
+    Bool(bool),
+}
+
+pub struct Type4m<'a> {
+    diag: &'a mut Diag4m,
+    // Whether every variable must be declared before it is used
+    strict: bool,
+    // Type of INTEGER, undeclared variables, literals and parameters
+    int_ty: Ty,
+    // Parameter types and result type of every subprogram, None for
+    // procedures
+    subprograms: HashMap<String, (Vec<Ty>, Option<Ty>)>,
+    // Type of each variable in the body being checked
+    var_types: HashMap<String, Ty>,
+    // Value of each constant in the body being checked
+    constants: HashMap<String, Value>,
+    // Types declared with TYPE, including imported ones
+    types: HashMap<String, Ty>,
+    // Type the context of the expression being checked expects
+    expected: Option<Ty>,
+}
+
+impl<'a> Type4m<'a> {
+    pub fn new(diag: &'a mut Diag4m, strict: bool, int_ty: Ty) -> Self {
+        Type4m {
+            diag,
+            strict,
+            int_ty,
+            subprograms: HashMap::new(),
+            var_types: HashMap::new(),
+            constants: HashMap::new(),
+            types: HashMap::new(),
+            expected: None,
+        }
+    }
+
+    // Annotate every expression and variable reference with its type
+    pub fn check(&mut self, ast: &mut Node) {
+        for child in ast.children_mut() {
+            match child.name().as_str() {
+                "TYPEDEF" => self.check_typedef(child),
+                "FUNCTION" | "PROCEDURE" => {
+                    let params = self.check_params(&mut child.children_mut()[0]);
+                    let ret = (child.name() == "FUNCTION").then_some(self.int_ty);
+                    if let Some(ret) = ret {
+                        child.set_ty(ret);
+                    }
+                    self.subprograms
+                        .insert(child.value().to_string(), (params, ret));
+                }
+                "EXTERN" => {
+                    let signature = self.check_extern(child);
+                    self.subprograms
+                        .insert(child.value().to_string(), signature);
+                }
+                "CONST" => self.check_const(child.children_mut()),
+                // Imported subprograms were checked with their module
+                "IMPORT" => {
+                    for item in child.children_mut() {
+                        if item.name() == "CONST" {
+                            self.check_const(item.children_mut());
+                        } else if item.name() == "TYPEDEF" {
+                            self.check_typedef(item);
+                        } else {
+                            let params = item.children()[0]
+                                .children()
+                                .iter()
+                                .map(|param| param.ty().unwrap_or(self.int_ty))
+                                .collect();
+                            self.subprograms
+                                .insert(item.value().to_string(), (params, item.ty()));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Global constants are visible in every body
+        let global_types = self.var_types.clone();
+        let global_constants = self.constants.clone();
+        for child in ast.children_mut() {
+            self.var_types = global_types.clone();
+            self.constants = global_constants.clone();
+            if matches!(
+                child.name().as_str(),
+                "EXTERN" | "IMPORT" | "CONST" | "MODULE" | "TYPEDEF"
+            ) {
+                continue;
+            }
+            if child.name() == "FUNCTION" || child.name() == "PROCEDURE" {
+                let children = child.children_mut();
+                // Parameters hide global constants
+                for param in children[0].children() {
+                    self.constants.remove(param.value());
+                    self.var_types
+                        .insert(param.value().to_string(), param.ty().unwrap());
+                }
+                self.check_statements(&mut children[1]);
+            } else {
+                self.check_statements(child);
+            }
+        }
+    }
+
+    fn check_statements(&mut self, node: &mut Node) {
+        for child in node.children_mut() {
+            let name = child.name().clone();
+            let children = child.children_mut();
+            match name.as_str() {
+                "DECLARE" => self.check_declare(children),
+                "CONST" => self.check_const(children),
+                "ASSIGNOP" | "COMPOUND" => {
+                    let (id, value) = children.split_at_mut(1);
+                    self.check_assign(&mut id[0], &mut value[0]);
+                }
+                "PARALLEL" => {
+                    let (targets, values) = children.split_at_mut(1);
+                    let targets = targets[0].children_mut();
+                    for (id, value) in targets.iter_mut().zip(values[0].children_mut()) {
+                        self.check_assign(id, value);
+                    }
+                }
+                "READ" => {
+                    for id in children {
+                        let ty = self.check_target(id);
+                        if ty.is_some_and(|ty| !ty.is_numeric()) {
+                            self.diag.error(
+                                id.loc(),
+                                format!(
+                                    "cannot read into `{}` of type {}",
+                                    id.value(),
+                                    ty.unwrap()
+                                ),
+                            );
+                        }
+                    }
+                }
+                "WRITE" => {
+                    for expr in children {
+                        if expr.name() == "STRINGLITERAL" {
+                            continue;
+                        }
+                        if let Some(ty) = self.check_expression(expr)
+                            && !ty.is_scalar()
+                        {
+                            self.diag
+                                .error(expr.loc(), format!("cannot write a value of type {}", ty));
+                        }
+                    }
+                }
+                "IF" | "WHILE" => {
+                    self.coerce(&mut children[0], Ty::Boolean);
+                    for stmts in &mut children[1..] {
+                        self.check_statements(stmts);
+                    }
+                }
+                "ASSERT" => self.coerce(&mut children[0], Ty::Boolean),
+                "REPEAT" => {
+                    self.check_statements(&mut children[0]);
+                    self.coerce(&mut children[1], Ty::Boolean);
+                }
+                // FOR children: ID, start, limit, step, body
+                "FOR" => {
+                    let ty = self.check_load(&mut children[0]);
+                    if !ty.is_integer() {
+                        self.diag.error(
+                            children[0].loc(),
+                            format!("FOR loop variable must be an integer, found {}", ty),
+                        );
+                    }
+                    for expr in &mut children[1..4] {
+                        self.coerce(expr, ty);
+                    }
+                    self.check_step(&children[3]);
+                    self.check_statements(&mut children[4]);
+                }
+                "CASE" => self.check_case(children),
+                "EXIT" => {
+                    let status = &mut children[0];
+                    if status.name() == "INTLITERAL" {
+                        self.check_intliteral(status, Ty::Integer);
+                    } else if let Some(ty) = self.check_expression(status)
+                        && !ty.is_integer()
+                    {
+                        self.diag.error(
+                            status.loc(),
+                            format!("EXIT status must be an integer, found {}", ty),
+                        );
+                    }
+                }
+                "RETURN" => {
+                    if let Some(value) = children.first_mut() {
+                        self.coerce(value, self.int_ty);
+                    }
+                }
+                "CALL" => {
+                    self.check_expression(child);
+                }
+                _ => self.check_statements(child),
+            }
+        }
+    }
+
+    // Parameters are INTEGER unless declared with a TYPE. Records are
+    // passed by reference; arrays cannot be passed.
+    fn check_params(&mut self, params: &mut Node) -> Vec<Ty> {
+        let mut tys = Vec::new();
+        for param in params.children_mut() {
+            let ty = match param.children().first() {
+                Some(ty_node) => {
+                    let ty = self.resolve_type(ty_node);
+                    if matches!(ty, Ty::Array(..)) {
+                        self.diag.error(
+                            ty_node.loc(),
+                            format!("parameter `{}` cannot be an array", param.value()),
+                        );
+                    }
+                    ty
+                }
+                None => self.int_ty,
+            };
+            param.set_ty(ty);
+            tys.push(ty);
+        }
+        tys
+    }
+
+    // TYPEDEF children: the TYPE it names. A RECORD is named after the
+    // declaration; any other TYPE is an alias.
+    fn check_typedef(&mut self, node: &Node) {
+        let ty_node = &node.children()[0];
+        let ty = if ty_node.value() == "RECORD" {
+            self.resolve_record(ty_node, node.value())
+        } else {
+            self.resolve_type(ty_node)
+        };
+        if self.types.contains_key(node.value()) {
+            self.diag.error(
+                node.loc(),
+                format!("type `{}` is already declared", node.value()),
+            );
+        } else {
+            self.types.insert(node.value().to_string(), ty);
+        }
+    }
+
+    // TYPE nodes name a scalar type or a declared type; an ARRAY has its
+    // length and element TYPE as children
+    fn resolve_type(&mut self, ty_node: &Node) -> Ty {
+        match ty_node.value().as_str() {
+            "INTEGER" => self.int_ty,
+            "BOOLEAN" => Ty::Boolean,
+            "LONG" => Ty::Long,
+            "REAL" => Ty::Real,
+            "ARRAY" => {
+                let children = ty_node.children();
+                let len = match self.array_len(&children[0]) {
+                    Some(len) if len > 0 => len,
+                    _ => {
+                        self.diag.error(
+                            children[0].loc(),
+                            format!("invalid array length `{}`", children[0].value()),
+                        );
+                        1
+                    }
+                };
+                let elem = self.resolve_type(&children[1]);
+                if elem.is_scalar() {
+                    Ty::array(elem, len)
+                } else {
+                    self.diag.error(
+                        children[1].loc(),
+                        "array elements must be scalars".to_string(),
+                    );
+                    elem
+                }
+            }
+            "RECORD" => self.resolve_record(ty_node, "RECORD"),
+            name => match self.types.get(name) {
+                Some(&ty) => ty,
+                None => {
+                    self.diag
+                        .error(ty_node.loc(), format!("unknown type `{}`", name));
+                    self.int_ty
+                }
+            },
+        }
+    }
+
+    // RECORD children: a FIELDS node for each group of fields, holding
+    // their IDs, then their TYPE
+    fn resolve_record(&mut self, ty_node: &Node, name: &str) -> Ty {
+        let mut fields: Vec<(String, Ty)> = Vec::new();
+        for group in ty_node.children() {
+            let (field_ty_node, ids) = group.children().split_last().unwrap();
+            let ty = self.resolve_type(field_ty_node);
+            if !ty.is_scalar() {
+                self.diag.error(
+                    field_ty_node.loc(),
+                    "record fields must be scalars".to_string(),
+                );
+            }
+            for id in ids {
+                if fields.iter().any(|(field, _)| field == id.value()) {
+                    self.diag
+                        .error(id.loc(), format!("duplicate field `{}`", id.value()));
+                } else {
+                    fields.push((id.value().to_string(), ty));
+                }
+            }
+        }
+        Ty::record(Record {
+            name: name.to_string(),
+            fields,
+        })
+    }
+
+    // An array length is an integer literal or the name of a constant
+    fn array_len(&self, node: &Node) -> Option<usize> {
+        match self.constants.get(node.value()) {
+            Some(Value::Int(value)) => usize::try_from(*value).ok(),
+            Some(_) => None,
+            None => node.value().parse().ok(),
+        }
+    }
+
+    // CONST children: a CONSTANT named after each constant, holding the
+    // expression it is defined by, which is replaced by its value. Constants
+    // are declared like variables but every use is replaced by their value.
+    fn check_const(&mut self, children: &mut [Node]) {
+        for constant in children {
+            let Some(value) = self.evaluate(&constant.children()[0]) else {
+                continue;
+            };
+            Self::set_literal(&mut constant.children_mut()[0], value);
+            let ty = self.value_ty(value);
+            if self.var_types.contains_key(constant.value()) {
+                self.diag.error(
+                    constant.loc(),
+                    format!("`{}` is already declared", constant.value()),
+                );
+            } else {
+                self.var_types.insert(constant.value().to_string(), ty);
+                self.constants.insert(constant.value().to_string(), value);
+            }
+            constant.set_ty(ty);
+        }
+    }
+
+    fn value_ty(&self, value: Value) -> Ty {
+        match value {
+            Value::Int(_) => self.int_ty,
+            Value::Real(_) => Ty::Real,
+            Value::Bool(_) => Ty::Boolean,
+        }
+    }
+
+    // Replace a reference to a constant by a literal of its value
+    fn inline_constant(&self, node: &mut Node) {
+        if node.name() != "ID" {
+            return;
+        }
+        if let Some(&value) = self.constants.get(node.value()) {
+            Self::set_literal(node, value);
+        }
+    }
+
+    fn set_literal(node: &mut Node, value: Value) {
+        let (name, value) = match value {
+            Value::Int(value) => ("INTLITERAL", value.to_string()),
+            Value::Real(value) => ("REALLITERAL", format!("{:?}", value)),
+            Value::Bool(value) => ("BOOLLITERAL", value.to_string()),
+        };
+        node.set_name(name.to_string());
+        node.set_value(value);
+        node.take_children();
+    }
+
+    // Value of a constant expression, or None if it is not one or it
+    // cannot be computed, which has already been reported
+    fn evaluate(&mut self, node: &Node) -> Option<Value> {
+        let children = node.children();
+        match node.name().as_str() {
+            "INTLITERAL" => match node.value().parse::<i64>() {
+                Ok(value) if self.int_ty.fits(value) => Some(Value::Int(value)),
+                _ => {
+                    self.diag.error(
+                        node.loc(),
+                        format!(
+                            "integer literal `{}` is out of range for {}",
+                            node.value(),
+                            self.int_ty
+                        ),
+                    );
+                    None
+                }
+            },
+            "REALLITERAL" => match node.value().parse::<f64>() {
+                Ok(value) if value.is_finite() => Some(Value::Real(value)),
+                _ => {
+                    self.diag.error(
+                        node.loc(),
+                        format!("real literal `{}` is out of range", node.value()),
+                    );
+                    None
+                }
+            },
+            "BOOLLITERAL" => Some(Value::Bool(node.value() == "true")),
+            "ID" if self.constants.contains_key(node.value()) => {
+                self.constants.get(node.value()).copied()
+            }
+            "ID" | "INDEX" | "FIELD" | "CALL" => {
+                self.diag
+                    .error(node.loc(), format!("`{}` is not a constant", node.value()));
+                None
+            }
+            "NOT" => match self.evaluate(&children[0])? {
+                Value::Bool(value) => Some(Value::Bool(!value)),
+                value => {
+                    self.mismatched(children[0].loc(), Ty::Boolean, value);
+                    None
+                }
+            },
+            name => {
+                let lhs = self.evaluate(&children[0])?;
+                let rhs = self.evaluate(&children[1])?;
+                self.evaluate_binary(name, node.loc(), lhs, rhs)
+            }
+        }
+    }
+
+    fn shift_mask(&self) -> i64 {
+        match self.int_ty {
+            Ty::Long => 63,
+            _ => 31,
+        }
+    }
+
+    fn mismatched(&mut self, loc: Loc, expected: Ty, value: Value) {
+        let found = self.value_ty(value);
+        self.diag.error(
+            loc,
+            format!("mismatched types: expected {}, found {}", expected, found),
+        );
+    }
+
+    fn evaluate_binary(&mut self, name: &str, loc: Loc, lhs: Value, rhs: Value) -> Option<Value> {
+        let value = match (lhs, rhs) {
+            (Value::Int(lhs), Value::Int(rhs)) => {
+                let value = match name {
+                    "PLUSOP" => lhs.checked_add(rhs),
+                    "MINUSOP" => lhs.checked_sub(rhs),
+                    "MULTIOP" => lhs.checked_mul(rhs),
+                    "DIVIDEOP" | "MOD" if rhs == 0 => {
+                        self.diag
+                            .error(loc, "division by zero in constant expression".to_string());
+                        return None;
+                    }
+                    "DIVIDEOP" => lhs.checked_div(rhs),
+                    "MOD" => lhs.checked_rem(rhs),
+                    "POWOP" => Self::power(lhs, rhs),
+                    // The shift amount is taken modulo the width, as in the generated code
+                    "SHL" => lhs.checked_mul(1 << (rhs & self.shift_mask())),
+                    "SHR" => Some(lhs >> (rhs & self.shift_mask())),
+                    "AND" => Some(lhs & rhs),
+                    "OR" => Some(lhs | rhs),
+                    "XOR" => Some(lhs ^ rhs),
+                    _ => return Some(Value::Bool(Self::compare(name, lhs.cmp(&rhs)))),
+                };
+                match value {
+                    Some(value) if self.int_ty.fits(value) => Value::Int(value),
+                    _ => {
+                        self.diag.error(
+                            loc,
+                            "arithmetic overflow in constant expression".to_string(),
+                        );
+                        return None;
+                    }
+                }
+            }
+            (Value::Bool(lhs), Value::Bool(rhs)) => match name {
+                "AND" => Value::Bool(lhs && rhs),
+                "OR" => Value::Bool(lhs || rhs),
+                "XOR" | "NEOP" => Value::Bool(lhs != rhs),
+                "EQOP" => Value::Bool(lhs == rhs),
+                _ => {
+                    self.mismatched(loc, self.int_ty, Value::Bool(lhs));
+                    return None;
+                }
+            },
+            (Value::Bool(_), value) | (value, Value::Bool(_))
+                if matches!(name, "AND" | "OR" | "XOR" | "EQOP" | "NEOP") =>
+            {
+                self.mismatched(loc, Ty::Boolean, value);
+                return None;
+            }
+            (Value::Bool(value), _) | (_, Value::Bool(value)) => {
+                self.mismatched(loc, self.int_ty, Value::Bool(value));
+                return None;
+            }
+            // Mixed operands are widened to REAL
+            (lhs, rhs) => {
+                let lhs = Self::real(lhs);
+                let rhs = Self::real(rhs);
+                let value = match name {
+                    "PLUSOP" => lhs + rhs,
+                    "MINUSOP" => lhs - rhs,
+                    "MULTIOP" => lhs * rhs,
+                    "DIVIDEOP" if rhs == 0.0 => {
+                        self.diag
+                            .error(loc, "division by zero in constant expression".to_string());
+                        return None;
+                    }
+                    "DIVIDEOP" => lhs / rhs,
+                    "MOD" | "POWOP" | "SHL" | "SHR" | "AND" | "OR" | "XOR" => {
+                        self.mismatched(loc, self.int_ty, Value::Real(lhs));
+                        return None;
+                    }
+                    _ => {
+                        let ordering = lhs.partial_cmp(&rhs)?;
+                        return Some(Value::Bool(Self::compare(name, ordering)));
+                    }
+                };
+                if !value.is_finite() {
+                    self.diag.error(
+                        loc,
+                        "arithmetic overflow in constant expression".to_string(),
+                    );
+                    return None;
+                }
+                Value::Real(value)
+            }
+        };
+        Some(value)
+    }
+
+    fn real(value: Value) -> f64 {
+        match value {
+            Value::Int(value) => value as f64,
+            Value::Real(value) => value,
+            Value::Bool(_) => unreachable!(),
+        }
+    }
+
+    fn compare(name: &str, ordering: Ordering) -> bool {
+        match name {
+            "LTOP" => ordering.is_lt(),
+            "LEOP" => ordering.is_le(),
+            "GTOP" => ordering.is_gt(),
+            "GEOP" => ordering.is_ge(),
+            "EQOP" => ordering.is_eq(),
+            _ => ordering.is_ne(),
+        }
+    }
+
+    // Integer power like the runtime's, where a negative exponent
+    // truncates the fraction towards zero
+    fn power(base: i64, exponent: i64) -> Option<i64> {
+        if exponent < 0 {
+            return Some(match base {
+                1 => 1,
+                -1 if exponent % 2 == 0 => 1,
+                -1 => -1,
+                _ => 0,
+            });
+        }
+        base.checked_pow(u32::try_from(exponent).ok()?)
+    }
+
+    // CASE children: selector, ARMs of labels and a statement list, then the
+    // ELSE statement list if any. A label is an integer literal or constant,
+    // and no two labels may have the same value.
+    fn check_case(&mut self, children: &mut [Node]) {
+        let (selector, arms) = children.split_first_mut().unwrap();
+        let ty = self.check_expression(selector);
+        if let Some(ty) = ty
+            && !ty.is_integer()
+        {
+            self.diag.error(
+                selector.loc(),
+                format!("CASE selector must be an integer, found {}", ty),
+            );
+        }
+
+        let mut seen: HashMap<i64, Loc> = HashMap::new();
+        for arm in arms {
+            if arm.name() != "ARM" {
+                self.check_statements(arm);
+                continue;
+            }
+            let (stmts, labels) = arm.children_mut().split_last_mut().unwrap();
+            for label in labels {
+                let name = label.value().clone();
+                self.inline_constant(label);
+                if label.name() != "INTLITERAL" {
+                    self.diag.error(
+                        label.loc(),
+                        format!("CASE label `{}` is not an integer constant", name),
+                    );
+                    continue;
+                }
+                self.check_intliteral(label, ty.unwrap_or(self.int_ty));
+                let Ok(value) = label.value().parse::<i64>() else {
+                    continue;
+                };
+                if let Some(first) = seen.get(&value) {
+                    self.diag.error(
+                        label.loc(),
+                        format!(
+                            "duplicate CASE label `{}`, first used at line {}",
+                            label.value(),
+                            first.line
+                        ),
+                    );
+                } else {
+                    seen.insert(value, label.loc());
+                }
+            }
+            self.check_statements(stmts);
+        }
+    }
+
+    // A FOR loop STEP is a literal or a constant, which coerce has replaced
+    // by its value and checked against the loop variable's type
+    fn check_step(&mut self, step: &Node) {
+        let constant = match step.name().as_str() {
+            "INTLITERAL" => step.value().parse::<i64>().ok(),
+            _ => None,
+        };
+        if constant.is_none_or(|step| step == 0) {
+            self.diag.error(
+                step.loc(),
+                "FOR loop STEP must be a nonzero integer constant".to_string(),
+            );
+        }
+    }
+
+    // DECLARE children: IDs, then the TYPE they are declared with
+    fn check_declare(&mut self, children: &mut [Node]) {
+        let (ty_node, ids) = children.split_last_mut().unwrap();
+        let ty = self.resolve_type(ty_node);
+
+        for id in ids {
+            if self.var_types.contains_key(id.value()) {
+                self.diag
+                    .error(id.loc(), format!("`{}` is already declared", id.value()));
+            } else {
+                self.var_types.insert(id.value().to_string(), ty);
+            }
+            id.set_ty(ty);
+        }
+    }
+
+    // Type of a variable that is used before anything was stored into it
+    fn undeclared(&mut self, id: &Node, ty: Ty) -> Ty {
+        if self.strict {
+            self.diag
+                .error(id.loc(), format!("`{}` is not declared", id.value()));
+        }
+        self.var_types.insert(id.value().to_string(), ty);
+        ty
+    }
+
+    // Type of a variable, which defaults to INTEGER
+    fn check_load(&mut self, id: &mut Node) -> Ty {
+        let ty = match self.var_types.get(id.value()) {
+            Some(&ty) => ty,
+            None => self.undeclared(id, self.int_ty),
+        };
+        id.set_ty(ty);
+        ty
+    }
+
+    // Element type of `a[i]`; the index is converted to an integer
+    fn check_index(&mut self, node: &mut Node) -> Option<Ty> {
+        self.coerce(&mut node.children_mut()[0], self.int_ty);
+        match self.var_types.get(node.value()) {
+            Some(&Ty::Array(&elem, _)) => {
+                node.set_ty(elem);
+                Some(elem)
+            }
+            _ => {
+                self.diag
+                    .error(node.loc(), format!("`{}` is not an array", node.value()));
+                None
+            }
+        }
+    }
+
+    // Type of `r.f`
+    fn check_field(&mut self, node: &mut Node) -> Option<Ty> {
+        let record = &mut node.children_mut()[0];
+        let Ty::Record(fields) = self.check_load(record) else {
+            self.diag.error(
+                record.loc(),
+                format!("`{}` is not a record", record.value()),
+            );
+            return None;
+        };
+        match fields.field(node.value()) {
+            Some((_, ty)) => {
+                node.set_ty(ty);
+                Some(ty)
+            }
+            None => {
+                self.diag.error(
+                    node.loc(),
+                    format!("type {} has no field `{}`", fields.name, node.value()),
+                );
+                None
+            }
+        }
+    }
+
+    // Type of a READ target, which is a variable, an array element or a
+    // record field
+    fn check_target(&mut self, target: &mut Node) -> Option<Ty> {
+        match target.name().as_str() {
+            "INDEX" => self.check_index(target),
+            "FIELD" => self.check_field(target),
+            _ => Some(self.check_load(target)),
+        }
+    }
+
+    // Without a declaration a variable takes the type of the first value
+    // assigned to it. Arrays and records can only be assigned element by
+    // element.
+    fn check_assign(&mut self, id: &mut Node, value: &mut Node) {
+        let ty = match id.name().as_str() {
+            "INDEX" => self.check_index(id),
+            "FIELD" => self.check_field(id),
+            _ => self.var_types.get(id.value()).copied(),
+        };
+        let ty = match ty {
+            Some(ty) => {
+                id.set_ty(ty);
+                self.coerce(value, ty);
+                Some(ty)
+            }
+            None if id.name() == "INDEX" || id.name() == "FIELD" => {
+                self.check_expression(value);
+                None
+            }
+            None => {
+                let ty = self.check_expression(value);
+                if let Some(ty) = ty {
+                    self.undeclared(id, ty);
+                    id.set_ty(ty);
+                }
+                ty
+            }
+        };
+        if let Some(ty) = ty
+            && !ty.is_scalar()
+        {
+            self.diag.error(
+                id.loc(),
+                format!("cannot assign to `{}` of type {}", id.value(), ty),
+            );
+        }
+    }
+
+    // Check that an expression has type `expected`, widening it if needed
+    fn coerce(&mut self, node: &mut Node, expected: Ty) {
+        self.inline_constant(node);
+        // Literals take the type their context expects
+        if node.name() == "INTLITERAL" && expected.is_integer() {
+            self.check_intliteral(node, expected);
+            return;
+        }
+
+        let outer = self.expected.replace(expected);
+        let ty = self.check_expression(node);
+        self.expected = outer;
+        match ty {
+            Some(ty) if ty == expected => {}
+            Some(ty) if ty.widens_to(expected) => Self::convert(node, expected),
+            Some(ty) => self.diag.error(
+                node.loc(),
+                format!("mismatched types: expected {}, found {}", expected, ty),
+            ),
+            None => {}
+        }
+    }
+
+    // Wrap `node` in a CONVERT to `ty`
+    fn convert(node: &mut Node, ty: Ty) {
+        let mut convert_node = Node::new("CONVERT".to_string(), ty.to_string());
+        convert_node.set_loc(node.loc());
+        convert_node.set_ty(ty);
+        let inner = std::mem::replace(node, convert_node);
+        node.add_child(inner);
+    }
+
+    fn check_intliteral(&mut self, node: &mut Node, ty: Ty) {
+        match node.value().parse::<i64>() {
+            Ok(value) if ty.fits(value) => {}
+            _ => self.diag.error(
+                node.loc(),
+                format!(
+                    "integer literal `{}` is out of range for {}",
+                    node.value(),
+                    ty
+                ),
+            ),
+        }
+        node.set_ty(ty);
+    }
+
+    // Operand types; literals count as INTEGER until the others are known
+    fn operand_tys(&mut self, children: &mut [Node]) -> Vec<Option<Ty>> {
+        let mut tys = Vec::new();
+        for child in children.iter_mut() {
+            self.inline_constant(child);
+            if child.name() == "INTLITERAL" {
+                tys.push(Some(self.int_ty));
+            } else {
+                tys.push(self.check_expression(child));
+            }
+        }
+        tys
+    }
+
+    // Widen numeric operands to their common type: REAL if either is REAL,
+    // otherwise LONG if either is LONG. Operands that are all literals take
+    // the integer type the context expects, so that a LONG can be assigned
+    // 3000000000 + 1.
+    fn unify_numeric(&mut self, children: &mut [Node], tys: &[Option<Ty>]) -> Ty {
+        let literals = children.iter().all(|child| child.name() == "INTLITERAL");
+        let ty = if tys.contains(&Some(Ty::Real)) {
+            Ty::Real
+        } else if tys.contains(&Some(Ty::Long)) {
+            Ty::Long
+        } else {
+            match self.expected {
+                Some(expected) if literals && expected.is_integer() => expected,
+                _ => self.int_ty,
+            }
+        };
+        for (child, child_ty) in children.iter_mut().zip(tys) {
+            if child.name() == "INTLITERAL" && ty.is_integer() {
+                self.check_intliteral(child, ty);
+            } else if child.name() == "INTLITERAL" {
+                self.check_intliteral(child, self.int_ty);
+                Self::convert(child, ty);
+            } else if child_ty.is_some_and(|child_ty| child_ty != ty) {
+                Self::convert(child, ty);
+            }
+        }
+        ty
+    }
+
+    // Operands of an arithmetic or ordering operator must be numeric
+    fn check_numeric_operands(&mut self, children: &mut [Node]) -> Option<Ty> {
+        self.check_operands(children, Ty::is_numeric)
+    }
+
+    // Operands of MOD, SHL, SHR and ** must be integers
+    fn check_integer_operands(&mut self, children: &mut [Node]) -> Option<Ty> {
+        self.check_operands(children, Ty::is_integer)
+    }
+
+    fn check_operands(&mut self, children: &mut [Node], accept: fn(&Ty) -> bool) -> Option<Ty> {
+        let tys = self.operand_tys(children);
+        let mut well_typed = true;
+        for (child, ty) in children.iter().zip(&tys) {
+            if let Some(ty) = ty
+                && !accept(ty)
+            {
+                self.diag.error(
+                    child.loc(),
+                    format!("mismatched types: expected {}, found {}", self.int_ty, ty),
+                );
+                well_typed = false;
+            }
+        }
+        well_typed.then(|| self.unify_numeric(children, &tys))
+    }
+
+    // EXTERN children: parameter list of TYPEs, then the result TYPE of a
+    // function. Only scalars can be passed to and returned from C.
+    fn check_extern(&mut self, node: &mut Node) -> (Vec<Ty>, Option<Ty>) {
+        let name = node.value().clone();
+        let children = node.children_mut();
+        let mut params = Vec::new();
+        for ty_node in children[0].children_mut() {
+            params.push(self.check_extern_type(&name, ty_node));
+        }
+        let ret = children
+            .get_mut(1)
+            .map(|ty_node| self.check_extern_type(&name, ty_node));
+        if let Some(ret) = ret {
+            node.set_ty(ret);
+        }
+        (params, ret)
+    }
+
+    fn check_extern_type(&mut self, name: &str, ty_node: &mut Node) -> Ty {
+        let ty = self.resolve_type(ty_node);
+        if !ty.is_scalar() {
+            self.diag.error(
+                ty_node.loc(),
+                format!("EXTERN `{}` cannot take or return {}", name, ty),
+            );
+        }
+        ty_node.set_ty(ty);
+        ty
+    }
+
+    // Arguments are converted to the parameter types
+    fn check_call(&mut self, node: &mut Node) -> Option<Ty> {
+        let (params, ret) = self
+            .subprograms
+            .get(node.value())
+            .cloned()
+            .unwrap_or_default();
+        for (i, arg) in node.children_mut().iter_mut().enumerate() {
+            self.coerce(arg, params.get(i).copied().unwrap_or(self.int_ty));
+        }
+        ret
+    }
+
+    // A call to a builtin that no subprogram hides becomes a BUILTIN node
+    // named in upper case
+    fn resolve_builtin(&self, node: &mut Node) {
+        if node.name() == "CALL"
+            && !self.subprograms.contains_key(node.value())
+            && sema4m::builtin_arity(node.value()).is_some()
+        {
+            node.set_name("BUILTIN".to_string());
+            node.set_value(node.value().to_uppercase());
+        }
+    }
+
+    // ABS, MIN and MAX take numbers, the others integers. All but ODD
+    // return the type of their arguments.
+    fn check_builtin(&mut self, name: &str, args: &mut [Node]) -> Option<Ty> {
+        if sema4m::builtin_arity(name) != Some(args.len()) {
+            for arg in args {
+                self.check_expression(arg);
+            }
+            return None;
+        }
+        match name {
+            "ABS" | "MIN" | "MAX" => self.check_numeric_operands(args),
+            "ODD" => self.check_integer_operands(args).map(|_| Ty::Boolean),
+            _ => self.check_integer_operands(args),
+        }
+    }
+
+    // Type of an expression, or None if it is ill-typed and already reported
+    fn check_expression(&mut self, node: &mut Node) -> Option<Ty> {
+        self.inline_constant(node);
+        self.resolve_builtin(node);
+        let name = node.name().clone();
+        let children = node.children_mut();
+        let ty = match name.as_str() {
+            "INTLITERAL" => {
+                self.check_intliteral(node, self.int_ty);
+                Some(self.int_ty)
+            }
+            "REALLITERAL" => {
+                if !node.value().parse::<f64>().is_ok_and(f64::is_finite) {
+                    self.diag.error(
+                        node.loc(),
+                        format!("real literal `{}` is out of range", node.value()),
+                    );
+                }
+                Some(Ty::Real)
+            }
+            "BOOLLITERAL" => Some(Ty::Boolean),
+            "ID" => Some(self.check_load(node)),
+            "INDEX" => self.check_index(node),
+            "FIELD" => self.check_field(node),
+            "BUILTIN" => {
+                let builtin = node.value().clone();
+                self.check_builtin(&builtin, node.children_mut())
+            }
+            "CALL" => self.check_call(node),
+            "NOT" => {
+                self.coerce(&mut children[0], Ty::Boolean);
+                Some(Ty::Boolean)
+            }
+            // Logical on BOOLEAN operands, bitwise on integers
+            "AND" | "OR" | "XOR" => {
+                let tys = self.operand_tys(children);
+                if tys.iter().all(|ty| ty.is_some_and(|ty| ty.is_integer())) {
+                    Some(self.unify_numeric(children, &tys))
+                } else {
+                    for (child, ty) in children.iter().zip(&tys) {
+                        if let Some(ty) = ty
+                            && *ty != Ty::Boolean
+                        {
+                            self.diag.error(
+                                child.loc(),
+                                format!("mismatched types: expected BOOLEAN, found {}", ty),
+                            );
+                        }
+                    }
+                    Some(Ty::Boolean)
+                }
+            }
+            "MOD" | "SHL" | "SHR" | "POWOP" => self.check_integer_operands(children),
+            "EQOP" | "NEOP" => {
+                let tys = self.operand_tys(children);
+                match (tys[0], tys[1]) {
+                    (Some(lhs), Some(rhs)) if lhs.is_numeric() && rhs.is_numeric() => {
+                        self.unify_numeric(children, &tys);
+                    }
+                    (Some(lhs), Some(rhs)) if !lhs.is_scalar() || !rhs.is_scalar() => {
+                        self.diag.error(
+                            node.loc(),
+                            format!("mismatched types: cannot compare {} with {}", lhs, rhs),
+                        )
+                    }
+                    (Some(lhs), Some(rhs)) if lhs != rhs => self.diag.error(
+                        node.loc(),
+                        format!("mismatched types: cannot compare {} with {}", lhs, rhs),
+                    ),
+                    _ => {}
+                }
+                Some(Ty::Boolean)
+            }
+            "LTOP" | "LEOP" | "GTOP" | "GEOP" => {
+                self.check_numeric_operands(children);
+                Some(Ty::Boolean)
+            }
+            _ => self.check_numeric_operands(children),
+        };
+
+        if let Some(ty) = ty {
+            node.set_ty(ty);
+        }
+        ty
+    }
+}
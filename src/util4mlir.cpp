@@ -1,4 +1,11 @@
+#include <cmath>
+#include <cstdlib>
 #include <iostream>
+#include <random>
+
+// Generator behind RANDOM, seeded from the system unless the program calls
+// random_seed first
+static std::mt19937_64 engine(std::random_device{}());
 
 extern "C" {
     int read() {
@@ -7,7 +14,116 @@ extern "C" {
         return value;
     }
 
+    long long read_long() {
+        long long value;
+        std::cin >> value;
+        return value;
+    }
+
+    double readreal() {
+        double value;
+        std::cin >> value;
+        return value;
+    }
+
+    // Integer power by repeated squaring, wrapping on overflow. A negative
+    // exponent truncates the fraction towards zero.
+    long long power_long(long long base, long long exponent) {
+        if (exponent < 0) {
+            if (base == 1 || (base == -1 && exponent % 2 == 0)) {
+                return 1;
+            }
+            return base == -1 ? -1 : 0;
+        }
+        unsigned long long result = 1;
+        unsigned long long factor = base;
+        while (exponent > 0) {
+            if (exponent & 1) {
+                result *= factor;
+            }
+            factor *= factor;
+            exponent >>= 1;
+        }
+        return result;
+    }
+
+    int power(int base, int exponent) {
+        return (int) power_long(base, exponent);
+    }
+
+    // Largest root with root * root <= value
+    long long isqrt_long(long long value) {
+        if (value < 0) {
+            std::cout.flush();
+            std::cerr << "ISQRT of negative number " << value << std::endl;
+            std::exit(1);
+        }
+        long long root = (long long) std::sqrt((double) value);
+        while (root > 0 && root > value / root) {
+            root--;
+        }
+        while (root + 1 <= value / (root + 1)) {
+            root++;
+        }
+        return root;
+    }
+
+    int isqrt(int value) {
+        return (int) isqrt_long(value);
+    }
+
+    void random_seed(long long seed) {
+        engine.seed(seed);
+    }
+
+    // Uniformly distributed in [0, bound)
+    long long random_long(long long bound) {
+        if (bound <= 0) {
+            std::cout.flush();
+            std::cerr << "RANDOM bound must be positive, found " << bound << std::endl;
+            std::exit(1);
+        }
+        return std::uniform_int_distribution<long long>(0, bound - 1)(engine);
+    }
+
+    int random_int(int bound) {
+        return (int) random_long(bound);
+    }
+
     void print(int value) {
         std::cout << value << std::endl;
     }
-}
\ No newline at end of file
+
+    void print_long(long long value) {
+        std::cout << value << std::endl;
+    }
+
+    void writereal(double value) {
+        std::cout << value << std::endl;
+    }
+
+    void print_bool(bool value) {
+        std::cout << (value ? "true" : "false") << std::endl;
+    }
+
+    void print_str(const char *value) {
+        std::cout << value;
+    }
+
+    void check_bounds(long long index, long long length, int line) {
+        if (index < 0 || index >= length) {
+            std::cout.flush();
+            std::cerr << "line " << line << ": index " << index
+                      << " out of bounds for array of length " << length << std::endl;
+            std::abort();
+        }
+    }
+
+    void check_assert(bool cond, const char *message) {
+        if (!cond) {
+            std::cout.flush();
+            std::cerr << message << std::endl;
+            std::exit(1);
+        }
+    }
+}
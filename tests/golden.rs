@@ -0,0 +1,376 @@
+// Golden tests: each tests/golden/<name>.m is compiled and the output is
+// compared with tests/golden/<name>.mlir or <name>.tac. The diagnostics of a
+// program that is rejected, or only warned about, are compared with
+// <name>.err. Run with UPDATE_GOLDEN=1 to rewrite the expected files after
+// an intended change.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+// The compiler writes cst.dot and ast.dot into its working directory, so
+// every test runs in a scratch directory with a copy of the sources
+fn compile(name: &str, args: &[&str]) -> (Output, String) {
+    let work = std::env::temp_dir().join(format!("microc-golden-{}-{}", std::process::id(), name));
+    std::fs::create_dir_all(&work).unwrap();
+    for entry in std::fs::read_dir(golden_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|extension| extension == "m") {
+            std::fs::copy(&path, work.join(path.file_name().unwrap())).unwrap();
+        }
+    }
+    let output = Command::new(env!("CARGO_BIN_EXE_microc"))
+        .current_dir(&work)
+        .arg(format!("{}.m", name))
+        .args(args)
+        .args(["-o", "out"])
+        .output()
+        .unwrap();
+    let out = std::fs::read_to_string(work.join("out")).unwrap_or_default();
+    std::fs::remove_dir_all(&work).unwrap();
+    (output, out)
+}
+
+fn compare(name: &str, extension: &str, actual: &str) {
+    let expected = golden_dir().join(format!("{}.{}", name, extension));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&expected, actual).unwrap();
+        return;
+    }
+    let contents = std::fs::read_to_string(&expected)
+        .unwrap_or_else(|_| panic!("missing {}", expected.display()));
+    assert_eq!(
+        actual,
+        contents,
+        "output differs from {}",
+        expected.display()
+    );
+}
+
+fn check_mlir(name: &str, args: &[&str]) {
+    let (output, mlir) = compile(name, &[args, &["--emit", "mlir"]].concat());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        output.status.success(),
+        "microc failed on {}.m:\n{}",
+        name,
+        stderr
+    );
+    compare(name, "mlir", &mlir);
+}
+
+fn check_tac(name: &str, args: &[&str]) {
+    let (output, tac) = compile(name, &[args, &["--emit", "tac"]].concat());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        output.status.success(),
+        "microc failed on {}.m:\n{}",
+        name,
+        stderr
+    );
+    compare(name, "tac", &tac);
+}
+
+fn check_warnings(name: &str, args: &[&str]) {
+    let (output, _) = compile(name, args);
+    assert!(output.status.success(), "microc rejected {}.m", name);
+    compare(name, "err", &String::from_utf8_lossy(&output.stderr));
+}
+
+fn check_errors(name: &str, args: &[&str]) {
+    let (output, _) = compile(name, args);
+    assert!(!output.status.success(), "microc accepted {}.m", name);
+    compare(name, "err", &String::from_utf8_lossy(&output.stderr));
+}
+
+// `b := a AND b` reads b before the result is written back
+#[test]
+fn logical_self() {
+    check_mlir("logical_self", &[]);
+}
+
+// A REAL that is only assigned on one path starts out as 0.0
+#[test]
+fn real_zero_init() {
+    check_mlir("real_zero_init", &[]);
+}
+
+// REAL <> is true for NaN, so that it is the negation of =
+#[test]
+fn real_ne() {
+    check_mlir("real_ne", &[]);
+}
+
+// `a[RANDOM(3)] += 1` evaluates the index once
+#[test]
+fn compound_index() {
+    check_mlir("compound_index", &[]);
+}
+
+// A CONST is accepted as the STEP of a FOR loop
+#[test]
+fn const_step() {
+    check_mlir("const_step", &[]);
+}
+
+// STEP literals are range-checked against the INTEGER width
+#[test]
+fn step_range() {
+    check_errors("step_range", &[]);
+}
+
+// A CONST is accepted as a CASE label and does not end the previous arm
+#[test]
+fn case_const() {
+    check_mlir("case_const", &[]);
+}
+
+// The limit of `FOR x := 1 TO x` is read before x is assigned
+#[test]
+fn for_self_limit() {
+    check_mlir("for_self_limit", &[]);
+}
+
+// Shift amounts are taken modulo the width, in constants as well
+#[test]
+fn shift_mask() {
+    check_mlir("shift_mask", &[]);
+}
+
+// Bitwise AND binds like *, OR and XOR like +, all tighter than comparisons
+#[test]
+fn bitwise_precedence() {
+    check_mlir("bitwise_precedence", &[]);
+}
+
+// Comparisons joined by AND must be parenthesized
+#[test]
+fn chained_comparison() {
+    check_errors("chained_comparison", &[]);
+}
+
+// CONST declarations are accepted before BEGIN
+#[test]
+fn program_const() {
+    check_mlir("program_const", &[]);
+}
+
+// A statement the parser cannot read is reported instead of dropped
+#[test]
+fn expected_end() {
+    check_errors("expected_end", &[]);
+}
+
+// A WRITE that ends with a string still ends its line
+#[test]
+fn write_lines() {
+    check_mlir("write_lines", &[]);
+}
+
+// Subprograms a module does not export are private symbols
+#[test]
+fn module_private() {
+    check_mlir("counter", &[]);
+}
+
+// An importer calls the module's exported subprograms by their mangled names
+#[test]
+fn module_import() {
+    check_mlir("import_counter", &[]);
+}
+
+// Literals take the integer type their context expects
+#[test]
+fn long_literals() {
+    check_mlir("long_literals", &[]);
+}
+
+// Outside a LONG context they are still checked against INTEGER
+#[test]
+fn integer_literal_range() {
+    check_errors("integer_literal_range", &[]);
+}
+
+// A misspelled --print-after pass is rejected like a misspelled --passes
+#[test]
+fn print_unknown_pass() {
+    check_errors("print_unknown_pass", &["--print-after=csee"]);
+}
+
+// Statements are lowered to basic blocks that list their predecessors
+#[test]
+fn tac_cfg() {
+    check_tac("tac_cfg", &[]);
+}
+
+// Dead stores and unused variables are reported, and removed with -O1
+#[test]
+fn dead_stores() {
+    check_warnings("dead_stores", &[]);
+    check_tac("dead_stores", &["-O1"]);
+}
+
+// Repeated constants and subexpressions are computed once with -O1
+#[test]
+fn value_numbering() {
+    check_mlir("value_numbering", &["-O1"]);
+}
+
+// SSA values are named after the variable and its version
+#[test]
+fn ssa_names() {
+    check_mlir("ssa_names", &[]);
+}
+
+// IF/ELSE joins the values assigned on either branch
+#[test]
+fn if_else() {
+    check_mlir("if_else", &[]);
+}
+
+// Variables assigned in a WHILE body are carried around the loop
+#[test]
+fn while_loop() {
+    check_mlir("while_loop", &[]);
+}
+
+// REPEAT runs its body before testing the condition
+#[test]
+fn repeat_until() {
+    check_mlir("repeat_until", &[]);
+}
+
+// The loop variable cannot be changed inside its FOR loop
+#[test]
+fn for_variable() {
+    check_errors("for_variable", &[]);
+}
+
+// Functions and procedures take parameters and can call themselves
+#[test]
+fn recursion() {
+    check_mlir("recursion", &[]);
+}
+
+// Conditions, NOT and BOOLEAN variables take BOOLEAN values, not integers
+#[test]
+fn boolean_condition() {
+    check_errors("boolean_condition", &[]);
+}
+
+// --strict-declarations requires DECLARE, and REAL does not narrow to INTEGER
+#[test]
+fn strict_declarations() {
+    check_errors("strict_declarations", &["--strict-declarations"]);
+}
+
+// --int-width=64 makes INTEGER an i64, with i64 literals and runtime calls
+#[test]
+fn int_width() {
+    check_mlir("int_width", &["--int-width=64"]);
+}
+
+// INTEGER operands are promoted to REAL when mixed with REAL
+#[test]
+fn real_mixed() {
+    check_mlir("real_mixed", &[]);
+}
+
+// --bounds-check tests every index against the length of its array
+#[test]
+fn bounds_check() {
+    check_mlir("bounds_check", &["--bounds-check"]);
+}
+
+// Sparse CASE labels are lowered to a chain of comparisons
+#[test]
+fn case_sparse() {
+    check_mlir("case_sparse", &[]);
+}
+
+// A CASE label can be used only once
+#[test]
+fn case_duplicate() {
+    check_errors("case_duplicate", &[]);
+}
+
+// BREAK and CONTINUE jump out of the loop, EXIT returns its status from main
+#[test]
+fn loop_exits() {
+    check_mlir("loop_exits", &[]);
+}
+
+// BREAK must appear inside a loop
+#[test]
+fn break_outside() {
+    check_errors("break_outside", &[]);
+}
+
+// ASSERT reports its location and message when it fails
+#[test]
+fn assert_message() {
+    check_mlir("assert_message", &[]);
+}
+
+// --no-asserts removes every ASSERT
+#[test]
+fn assert_disabled() {
+    let (output, mlir) = compile("assert_message", &["--no-asserts", "--emit", "mlir"]);
+    assert!(output.status.success());
+    assert!(
+        !mlir.contains("call @check_assert"),
+        "--no-asserts left an assertion:\n{}",
+        mlir
+    );
+}
+
+// Every right-hand side of a parallel assignment is read before any target is written
+#[test]
+fn parallel_assign() {
+    check_mlir("parallel_assign", &[]);
+}
+
+// Constants are folded, even when they refer to earlier constants
+#[test]
+fn const_fold() {
+    check_mlir("const_fold", &[]);
+}
+
+// A constant cannot be assigned to or read into
+#[test]
+fn const_assign() {
+    check_errors("const_assign", &[]);
+}
+
+// Builtins are lowered inline or to runtime calls, RANDOM is seeded by --seed
+#[test]
+fn builtins() {
+    check_mlir("builtins", &["--seed", "7"]);
+}
+
+// A builtin is called with the number of arguments it takes
+#[test]
+fn builtin_arity() {
+    check_errors("builtin_arity", &[]);
+}
+
+// An EXTERN function is declared private and called like any other function
+#[test]
+fn extern_call() {
+    check_mlir("extern_call", &[]);
+}
+
+// Record fields are read and written in place, and a record is passed by reference
+#[test]
+fn records() {
+    check_mlir("records", &[]);
+}
+
+// -O2 folds sums and differences of literals before the -O1 passes run
+#[test]
+fn fold_o2() {
+    check_tac("fold_o2", &["-O2"]);
+}
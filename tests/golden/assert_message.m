@@ -0,0 +1,6 @@
+BEGIN
+  READ(n);
+  ASSERT(n > 0);
+  ASSERT(n < 100, "n is too large");
+  WRITE(n);
+END
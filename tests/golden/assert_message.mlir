@@ -0,0 +1,37 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+  llvm.mlir.global internal constant @str0("assertion failed at assert_message.m:3:3\00") : !llvm.array<41 x i8>
+  llvm.mlir.global internal constant @str1("assertion failed at assert_message.m:4:3: n is too large\00") : !llvm.array<57 x i8>
+
+  func.func @main() -> i32 {
+    %n_0 = call @read() : () -> i32
+    %tmp0 = arith.constant 0 : i32
+    %tmp1 = arith.cmpi sgt, %n_0, %tmp0 : i32
+    %tmp2 = llvm.mlir.addressof @str0 : !llvm.ptr
+    call @check_assert(%tmp1, %tmp2) : (i1, !llvm.ptr) -> ()
+    %tmp3 = arith.constant 100 : i32
+    %tmp4 = arith.cmpi slt, %n_0, %tmp3 : i32
+    %tmp5 = llvm.mlir.addressof @str1 : !llvm.ptr
+    call @check_assert(%tmp4, %tmp5) : (i1, !llvm.ptr) -> ()
+    call @print(%n_0) : (i32) -> ()
+    %tmp6 = arith.constant 0 : i32
+    return %tmp6 : i32
+  }
+}
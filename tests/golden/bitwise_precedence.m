@@ -0,0 +1,5 @@
+BEGIN
+  READ(x, y);
+  IF x AND 1 = 1 THEN WRITE(x SHL 1 OR 1); ENDIF
+  IF (x > 0) AND NOT (y > 0) OR (x = y) THEN WRITE(y XOR x AND 3); ENDIF
+END
@@ -0,0 +1,69 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %x_0 = call @read() : () -> i32
+    %y_0 = call @read() : () -> i32
+    %tmp0 = arith.constant 1 : i32
+    %tmp1 = arith.andi %x_0, %tmp0 : i32
+    %tmp2 = arith.constant 1 : i32
+    %tmp3 = arith.cmpi eq, %tmp1, %tmp2 : i32
+    cf.cond_br %tmp3, ^bb1(%x_0, %y_0 : i32, i32), ^bb2(%x_0, %y_0 : i32, i32)
+  ^bb1(%x_1: i32, %y_1: i32):
+    %tmp4 = arith.constant 1 : i32
+    %tmp5 = arith.constant 31 : i32
+    %tmp6 = arith.andi %tmp4, %tmp5 : i32
+    %tmp7 = arith.shli %x_1, %tmp6 : i32
+    %tmp8 = arith.constant 1 : i32
+    %tmp9 = arith.ori %tmp7, %tmp8 : i32
+    call @print(%tmp9) : (i32) -> ()
+    cf.br ^bb2(%x_1, %y_1 : i32, i32)
+  ^bb2(%x_2: i32, %y_2: i32):
+    %tmp10 = arith.constant 0 : i32
+    %tmp11 = arith.cmpi sgt, %x_2, %tmp10 : i32
+    cf.cond_br %tmp11, ^bb3(%x_2, %y_2 : i32, i32), ^bb4(%x_2, %y_2, %tmp11 : i32, i32, i1)
+  ^bb3(%x_3: i32, %y_3: i32):
+    %tmp12 = arith.constant 0 : i32
+    %tmp13 = arith.cmpi sgt, %y_3, %tmp12 : i32
+    %tmp14 = arith.constant 1 : i1
+    %tmp15 = arith.xori %tmp13, %tmp14 : i1
+    cf.br ^bb5(%x_3, %y_3, %tmp15 : i32, i32, i1)
+  ^bb4(%x_4: i32, %y_4: i32, %tmp16: i1):
+    cf.br ^bb5(%x_4, %y_4, %tmp16 : i32, i32, i1)
+  ^bb5(%x_5: i32, %y_5: i32, %tmp17: i1):
+    cf.cond_br %tmp17, ^bb7(%x_5, %y_5, %tmp17 : i32, i32, i1), ^bb6(%x_5, %y_5 : i32, i32)
+  ^bb7(%x_6: i32, %y_6: i32, %tmp18: i1):
+    cf.br ^bb8(%x_6, %y_6, %tmp18 : i32, i32, i1)
+  ^bb6(%x_7: i32, %y_7: i32):
+    %tmp19 = arith.cmpi eq, %x_7, %y_7 : i32
+    cf.br ^bb8(%x_7, %y_7, %tmp19 : i32, i32, i1)
+  ^bb8(%x_8: i32, %y_8: i32, %tmp20: i1):
+    cf.cond_br %tmp20, ^bb9(%x_8, %y_8 : i32, i32), ^bb10
+  ^bb9(%x_9: i32, %y_9: i32):
+    %tmp21 = arith.constant 3 : i32
+    %tmp22 = arith.andi %x_9, %tmp21 : i32
+    %tmp23 = arith.xori %y_9, %tmp22 : i32
+    call @print(%tmp23) : (i32) -> ()
+    cf.br ^bb10
+  ^bb10:
+    %tmp24 = arith.constant 0 : i32
+    return %tmp24 : i32
+  }
+}
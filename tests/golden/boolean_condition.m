@@ -0,0 +1,7 @@
+BEGIN
+  DECLARE b : BOOLEAN;
+  READ(x);
+  IF x THEN WRITE(1); ENDIF
+  b := 1;
+  WHILE NOT x DO x := 0; ENDWHILE
+END
@@ -0,0 +1,5 @@
+BEGIN
+  DECLARE a : ARRAY [4] OF INTEGER;
+  READ(i, a[i]);
+  WRITE(a[i + 1]);
+END
@@ -0,0 +1,43 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %a_0 = memref.alloca() : memref<4xi32>
+    %i_0 = call @read() : () -> i32
+    %tmp0 = call @read() : () -> i32
+    %tmp1 = arith.extsi %i_0 : i32 to i64
+    %tmp2 = arith.constant 4 : i64
+    %tmp3 = arith.constant 3 : i32
+    call @check_bounds(%tmp1, %tmp2, %tmp3) : (i64, i64, i32) -> ()
+    %tmp4 = arith.index_cast %i_0 : i32 to index
+    memref.store %tmp0, %a_0[%tmp4] : memref<4xi32>
+    %tmp5 = arith.constant 1 : i32
+    %tmp6 = arith.addi %i_0, %tmp5 : i32
+    %tmp7 = arith.extsi %tmp6 : i32 to i64
+    %tmp8 = arith.constant 4 : i64
+    %tmp9 = arith.constant 4 : i32
+    call @check_bounds(%tmp7, %tmp8, %tmp9) : (i64, i64, i32) -> ()
+    %tmp10 = arith.index_cast %tmp6 : i32 to index
+    %tmp11 = memref.load %a_0[%tmp10] : memref<4xi32>
+    call @print(%tmp11) : (i32) -> ()
+    %tmp12 = arith.constant 0 : i32
+    return %tmp12 : i32
+  }
+}
@@ -0,0 +1,4 @@
+BEGIN
+  READ(n);
+  IF n > 0 THEN BREAK; ENDIF
+END
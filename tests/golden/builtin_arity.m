@@ -0,0 +1,4 @@
+BEGIN
+  READ(x);
+  WRITE(MIN(x));
+END
@@ -0,0 +1,5 @@
+BEGIN
+  READ(x, y);
+  WRITE(ABS(x), MIN(x, y), MAX(x, y), ISQRT(y), RANDOM(6));
+  IF ODD(x) THEN WRITE(1); ENDIF
+END
@@ -0,0 +1,52 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %tmp0 = arith.constant 7 : i64
+    call @random_seed(%tmp0) : (i64) -> ()
+    %x_0 = call @read() : () -> i32
+    %y_0 = call @read() : () -> i32
+    %tmp1 = arith.constant 0 : i32
+    %tmp2 = arith.subi %tmp1, %x_0 : i32
+    %tmp3 = arith.maxsi %x_0, %tmp2 : i32
+    call @print(%tmp3) : (i32) -> ()
+    %tmp4 = arith.minsi %x_0, %y_0 : i32
+    call @print(%tmp4) : (i32) -> ()
+    %tmp5 = arith.maxsi %x_0, %y_0 : i32
+    call @print(%tmp5) : (i32) -> ()
+    %tmp6 = call @isqrt(%y_0) : (i32) -> i32
+    call @print(%tmp6) : (i32) -> ()
+    %tmp7 = arith.constant 6 : i32
+    %tmp8 = call @random_int(%tmp7) : (i32) -> i32
+    call @print(%tmp8) : (i32) -> ()
+    %tmp9 = arith.constant 1 : i32
+    %tmp10 = arith.andi %x_0, %tmp9 : i32
+    %tmp11 = arith.constant 0 : i32
+    %tmp12 = arith.cmpi ne, %tmp10, %tmp11 : i32
+    cf.cond_br %tmp12, ^bb1, ^bb2
+  ^bb1:
+    %tmp13 = arith.constant 1 : i32
+    call @print(%tmp13) : (i32) -> ()
+    cf.br ^bb2
+  ^bb2:
+    %tmp14 = arith.constant 0 : i32
+    return %tmp14 : i32
+  }
+}
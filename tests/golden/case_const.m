@@ -0,0 +1,11 @@
+BEGIN
+  CONST ONE = 1, TWO = ONE + 1;
+  READ(x);
+  CASE x OF
+    ONE, 3: WRITE(1);
+      y := 2;
+    TWO: WRITE(2);
+  ELSE
+    WRITE(9);
+  ENDCASE
+END
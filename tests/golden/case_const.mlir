@@ -0,0 +1,46 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %x_0 = call @read() : () -> i32
+    cf.switch %x_0 : i32, [
+      default: ^bb4,
+      1: ^bb1,
+      3: ^bb1,
+      2: ^bb2
+    ]
+  ^bb4:
+    %tmp0 = arith.constant 9 : i32
+    call @print(%tmp0) : (i32) -> ()
+    cf.br ^bb3
+  ^bb1:
+    %tmp1 = arith.constant 1 : i32
+    call @print(%tmp1) : (i32) -> ()
+    %y_0 = arith.constant 2 : i32
+    cf.br ^bb3
+  ^bb2:
+    %tmp2 = arith.constant 2 : i32
+    call @print(%tmp2) : (i32) -> ()
+    cf.br ^bb3
+  ^bb3:
+    %tmp3 = arith.constant 0 : i32
+    return %tmp3 : i32
+  }
+}
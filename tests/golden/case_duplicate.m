@@ -0,0 +1,7 @@
+BEGIN
+  READ(x);
+  CASE x OF
+    1, 2: WRITE(1);
+    2: WRITE(2);
+  ENDCASE
+END
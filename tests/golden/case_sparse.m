@@ -0,0 +1,7 @@
+BEGIN
+  READ(x);
+  CASE x OF
+    1: WRITE(1);
+    1000: WRITE(2);
+  ENDCASE
+END
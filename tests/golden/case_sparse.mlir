@@ -0,0 +1,44 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %x_0 = call @read() : () -> i32
+    %tmp0 = arith.constant 1 : i32
+    %tmp1 = arith.cmpi eq, %x_0, %tmp0 : i32
+    cf.cond_br %tmp1, ^bb1, ^bb4(%x_0 : i32)
+  ^bb1:
+    %tmp2 = arith.constant 1 : i32
+    call @print(%tmp2) : (i32) -> ()
+    cf.br ^bb3
+  ^bb4(%x_1: i32):
+    %tmp3 = arith.constant 1000 : i32
+    %tmp4 = arith.cmpi eq, %x_1, %tmp3 : i32
+    cf.cond_br %tmp4, ^bb2, ^bb5
+  ^bb2:
+    %tmp5 = arith.constant 2 : i32
+    call @print(%tmp5) : (i32) -> ()
+    cf.br ^bb3
+  ^bb5:
+    cf.br ^bb3
+  ^bb3:
+    %tmp6 = arith.constant 0 : i32
+    return %tmp6 : i32
+  }
+}
@@ -0,0 +1,4 @@
+BEGIN
+  READ(x, y);
+  IF x > 0 AND y > 0 THEN WRITE(x); ENDIF
+END
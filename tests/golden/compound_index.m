@@ -0,0 +1,5 @@
+BEGIN
+  DECLARE a : ARRAY [3] OF INTEGER;
+  a[RANDOM(3)] += 1;
+  WRITE(a[0], a[1], a[2]);
+END
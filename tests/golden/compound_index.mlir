@@ -0,0 +1,46 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %a_0 = memref.alloca() : memref<3xi32>
+    %tmp0 = arith.constant 3 : i32
+    %tmp1 = call @random_int(%tmp0) : (i32) -> i32
+    %tmp2 = arith.index_cast %tmp1 : i32 to index
+    %tmp3 = memref.load %a_0[%tmp2] : memref<3xi32>
+    %tmp4 = arith.constant 1 : i32
+    %tmp5 = arith.addi %tmp3, %tmp4 : i32
+    %tmp6 = arith.index_cast %tmp1 : i32 to index
+    memref.store %tmp5, %a_0[%tmp6] : memref<3xi32>
+    %tmp7 = arith.constant 0 : i32
+    %tmp8 = arith.index_cast %tmp7 : i32 to index
+    %tmp9 = memref.load %a_0[%tmp8] : memref<3xi32>
+    call @print(%tmp9) : (i32) -> ()
+    %tmp10 = arith.constant 1 : i32
+    %tmp11 = arith.index_cast %tmp10 : i32 to index
+    %tmp12 = memref.load %a_0[%tmp11] : memref<3xi32>
+    call @print(%tmp12) : (i32) -> ()
+    %tmp13 = arith.constant 2 : i32
+    %tmp14 = arith.index_cast %tmp13 : i32 to index
+    %tmp15 = memref.load %a_0[%tmp14] : memref<3xi32>
+    call @print(%tmp15) : (i32) -> ()
+    %tmp16 = arith.constant 0 : i32
+    return %tmp16 : i32
+  }
+}
@@ -0,0 +1,5 @@
+CONST N = 10;
+BEGIN
+  N := 11;
+  READ(N);
+END
@@ -0,0 +1,6 @@
+CONST N = 10, M = N * 2 + 1;
+BEGIN
+  DECLARE a : ARRAY [M] OF INTEGER;
+  a[N] := M;
+  WRITE(a[N]);
+END
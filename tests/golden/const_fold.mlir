@@ -0,0 +1,34 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %a_0 = memref.alloca() : memref<21xi32>
+    %tmp0 = arith.constant 21 : i32
+    %tmp1 = arith.constant 10 : i32
+    %tmp2 = arith.index_cast %tmp1 : i32 to index
+    memref.store %tmp0, %a_0[%tmp2] : memref<21xi32>
+    %tmp3 = arith.constant 10 : i32
+    %tmp4 = arith.index_cast %tmp3 : i32 to index
+    %tmp5 = memref.load %a_0[%tmp4] : memref<21xi32>
+    call @print(%tmp5) : (i32) -> ()
+    %tmp6 = arith.constant 0 : i32
+    return %tmp6 : i32
+  }
+}
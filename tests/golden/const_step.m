@@ -0,0 +1,4 @@
+BEGIN
+  CONST S = 2;
+  FOR i := 1 TO 10 STEP S DO WRITE(i); ENDFOR
+END
@@ -0,0 +1,39 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %tmp0 = arith.constant 1 : i32
+    %tmp1 = arith.constant 10 : i32
+    cf.br ^bb1(%tmp0, %tmp1 : i32, i32)
+  ^bb1(%i_0: i32, %tmp2: i32):
+    %tmp3 = arith.cmpi sle, %i_0, %tmp2 : i32
+    cf.cond_br %tmp3, ^bb2(%i_0, %tmp2 : i32, i32), ^bb4
+  ^bb2(%i_1: i32, %tmp4: i32):
+    call @print(%i_1) : (i32) -> ()
+    cf.br ^bb3(%i_1, %tmp4 : i32, i32)
+  ^bb3(%i_2: i32, %tmp5: i32):
+    %tmp6 = arith.constant 2 : i32
+    %i_3 = arith.addi %i_2, %tmp6 : i32
+    cf.br ^bb1(%i_3, %tmp5 : i32, i32)
+  ^bb4:
+    %tmp7 = arith.constant 0 : i32
+    return %tmp7 : i32
+  }
+}
@@ -0,0 +1,8 @@
+MODULE counter;
+EXPORT CONST START = 10;
+FUNCTION twice(n)
+  RETURN n * 2;
+END
+EXPORT FUNCTION next(n)
+  RETURN twice(n) + 1;
+END
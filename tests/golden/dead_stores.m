@@ -0,0 +1,7 @@
+BEGIN
+  READ(a);
+  b := a * 2;
+  b := a + 1;
+  c := 5;
+  WRITE(b);
+END
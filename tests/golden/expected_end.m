@@ -0,0 +1,4 @@
+BEGIN
+  INTEGER x;
+  WRITE(x);
+END
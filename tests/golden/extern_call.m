@@ -0,0 +1,5 @@
+EXTERN FUNCTION gcd(INTEGER, INTEGER) : INTEGER;
+BEGIN
+  READ(x, y);
+  WRITE(gcd(x, y));
+END
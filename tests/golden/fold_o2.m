@@ -0,0 +1,5 @@
+BEGIN
+  READ(y);
+  x := 2 + 3 - 1;
+  WRITE(x + y, y - (10 - 4));
+END
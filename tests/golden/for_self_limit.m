@@ -0,0 +1,4 @@
+BEGIN
+  x := 5;
+  FOR x := 1 TO x DO WRITE(x); ENDFOR
+END
@@ -0,0 +1,6 @@
+BEGIN
+  FOR i := 1 TO 10 DO
+    i := i + 1;
+    READ(i);
+  ENDFOR
+END
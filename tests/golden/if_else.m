@@ -0,0 +1,9 @@
+BEGIN
+  READ(a, b);
+  IF a <= b THEN
+    m := b;
+  ELSE
+    m := a;
+  ENDIF
+  IF m <> 0 THEN WRITE(m); ENDIF
+END
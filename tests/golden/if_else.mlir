@@ -0,0 +1,41 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %a_0 = call @read() : () -> i32
+    %b_0 = call @read() : () -> i32
+    %tmp0 = arith.cmpi sle, %a_0, %b_0 : i32
+    cf.cond_br %tmp0, ^bb1(%b_0 : i32), ^bb2(%a_0 : i32)
+  ^bb1(%b_1: i32):
+    cf.br ^bb3(%b_1 : i32)
+  ^bb2(%a_1: i32):
+    cf.br ^bb3(%a_1 : i32)
+  ^bb3(%m_0: i32):
+    %tmp1 = arith.constant 0 : i32
+    %tmp2 = arith.cmpi ne, %m_0, %tmp1 : i32
+    cf.cond_br %tmp2, ^bb4(%m_0 : i32), ^bb5
+  ^bb4(%m_1: i32):
+    call @print(%m_1) : (i32) -> ()
+    cf.br ^bb5
+  ^bb5:
+    %tmp3 = arith.constant 0 : i32
+    return %tmp3 : i32
+  }
+}
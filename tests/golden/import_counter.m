@@ -0,0 +1,4 @@
+IMPORT counter;
+BEGIN
+  WRITE(next(START));
+END
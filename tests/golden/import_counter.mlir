@@ -0,0 +1,29 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+  func.func private @micro_counter.next(i32) -> i32
+
+  func.func @main() -> i32 {
+    %tmp0 = arith.constant 10 : i32
+    %tmp1 = call @micro_counter.next(%tmp0) : (i32) -> i32
+    call @print(%tmp1) : (i32) -> ()
+    %tmp2 = arith.constant 0 : i32
+    return %tmp2 : i32
+  }
+}
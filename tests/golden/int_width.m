@@ -0,0 +1,7 @@
+BEGIN
+  DECLARE n : INTEGER;
+  DECLARE l : LONG;
+  READ(n);
+  l := n * 4000000000;
+  WRITE(l);
+END
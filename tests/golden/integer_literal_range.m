@@ -0,0 +1,5 @@
+BEGIN
+  DECLARE l : LONG;
+  l := 3000000000 + 1;
+  x := 3000000000 + 1;
+END
@@ -0,0 +1,8 @@
+BEGIN
+  DECLARE a, b, c, d : BOOLEAN;
+  a := TRUE; b := FALSE;
+  b := a AND b;
+  c := FALSE; d := TRUE;
+  d := c OR d;
+  WRITE(b, d);
+END
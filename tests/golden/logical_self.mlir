@@ -0,0 +1,43 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %a_0 = arith.constant 1 : i1
+    %b_0 = arith.constant 0 : i1
+    cf.cond_br %a_0, ^bb1(%b_0 : i1), ^bb2(%a_0 : i1)
+  ^bb1(%b_1: i1):
+    cf.br ^bb3(%b_1 : i1)
+  ^bb2(%a_1: i1):
+    cf.br ^bb3(%a_1 : i1)
+  ^bb3(%b_2: i1):
+    %c_0 = arith.constant 0 : i1
+    %d_0 = arith.constant 1 : i1
+    cf.cond_br %c_0, ^bb5(%b_2, %c_0 : i1, i1), ^bb4(%b_2, %d_0 : i1, i1)
+  ^bb5(%b_3: i1, %c_1: i1):
+    cf.br ^bb6(%b_3, %c_1 : i1, i1)
+  ^bb4(%b_4: i1, %d_1: i1):
+    cf.br ^bb6(%b_4, %d_1 : i1, i1)
+  ^bb6(%b_5: i1, %d_2: i1):
+    call @print_bool(%b_5) : (i1) -> ()
+    call @print_bool(%d_2) : (i1) -> ()
+    %tmp0 = arith.constant 0 : i32
+    return %tmp0 : i32
+  }
+}
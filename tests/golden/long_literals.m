@@ -0,0 +1,7 @@
+BEGIN
+  DECLARE a, b, c : LONG;
+  a := 3000000000 + 1;
+  b := (3000000000 - 1) * 2;
+  c := 2 ** 40;
+  WRITE(a, b, c);
+END
@@ -0,0 +1,39 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %tmp0 = arith.constant 3000000000 : i64
+    %tmp1 = arith.constant 1 : i64
+    %a_0 = arith.addi %tmp0, %tmp1 : i64
+    %tmp2 = arith.constant 3000000000 : i64
+    %tmp3 = arith.constant 1 : i64
+    %tmp4 = arith.subi %tmp2, %tmp3 : i64
+    %tmp5 = arith.constant 2 : i64
+    %b_0 = arith.muli %tmp4, %tmp5 : i64
+    %tmp6 = arith.constant 2 : i64
+    %tmp7 = arith.constant 40 : i64
+    %c_0 = call @power_long(%tmp6, %tmp7) : (i64, i64) -> i64
+    call @print_long(%a_0) : (i64) -> ()
+    call @print_long(%b_0) : (i64) -> ()
+    call @print_long(%c_0) : (i64) -> ()
+    %tmp8 = arith.constant 0 : i32
+    return %tmp8 : i32
+  }
+}
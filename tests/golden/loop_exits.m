@@ -0,0 +1,11 @@
+BEGIN
+  READ(n);
+  i := 0;
+  WHILE TRUE DO
+    i := i + 1;
+    IF i > n THEN BREAK; ENDIF
+    IF ODD(i) THEN CONTINUE; ENDIF
+    WRITE(i);
+  ENDWHILE
+  IF n < 0 THEN EXIT(2); ENDIF
+END
@@ -0,0 +1,58 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %n_0 = call @read() : () -> i32
+    %i_0 = arith.constant 0 : i32
+    cf.br ^bb1(%n_0, %i_0 : i32, i32)
+  ^bb1(%n_1: i32, %i_1: i32):
+    %tmp0 = arith.constant 1 : i1
+    cf.cond_br %tmp0, ^bb2(%n_1, %i_1 : i32, i32), ^bb3(%n_1 : i32)
+  ^bb2(%n_2: i32, %i_2: i32):
+    %tmp1 = arith.constant 1 : i32
+    %i_3 = arith.addi %i_2, %tmp1 : i32
+    %tmp2 = arith.cmpi sgt, %i_3, %n_2 : i32
+    cf.cond_br %tmp2, ^bb4(%n_2 : i32), ^bb5(%n_2, %i_3 : i32, i32)
+  ^bb4(%n_3: i32):
+    cf.br ^bb3(%n_3 : i32)
+  ^bb5(%n_4: i32, %i_4: i32):
+    %tmp3 = arith.constant 1 : i32
+    %tmp4 = arith.andi %i_4, %tmp3 : i32
+    %tmp5 = arith.constant 0 : i32
+    %tmp6 = arith.cmpi ne, %tmp4, %tmp5 : i32
+    cf.cond_br %tmp6, ^bb7(%n_4, %i_4 : i32, i32), ^bb8(%n_4, %i_4 : i32, i32)
+  ^bb7(%n_5: i32, %i_5: i32):
+    cf.br ^bb1(%n_5, %i_5 : i32, i32)
+  ^bb8(%n_6: i32, %i_6: i32):
+    call @print(%i_6) : (i32) -> ()
+    cf.br ^bb1(%n_6, %i_6 : i32, i32)
+  ^bb3(%n_7: i32):
+    %tmp7 = arith.constant 0 : i32
+    %tmp8 = arith.cmpi slt, %n_7, %tmp7 : i32
+    cf.cond_br %tmp8, ^bb10, ^bb11
+  ^bb10:
+    %tmp9 = arith.constant 2 : i32
+    call @exit(%tmp9) : (i32) -> ()
+    cf.br ^bb11
+  ^bb11:
+    %tmp10 = arith.constant 0 : i32
+    return %tmp10 : i32
+  }
+}
@@ -0,0 +1,9 @@
+BEGIN
+  READ(n);
+  a := 0;
+  b := 1;
+  FOR i := 1 TO n DO
+    a, b := b, a + b;
+  ENDFOR
+  WRITE(a);
+END
@@ -0,0 +1,42 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %n_0 = call @read() : () -> i32
+    %a_0 = arith.constant 0 : i32
+    %b_0 = arith.constant 1 : i32
+    %tmp0 = arith.constant 1 : i32
+    cf.br ^bb1(%a_0, %b_0, %tmp0, %n_0 : i32, i32, i32, i32)
+  ^bb1(%a_1: i32, %b_1: i32, %i_0: i32, %tmp1: i32):
+    %tmp2 = arith.cmpi sle, %i_0, %tmp1 : i32
+    cf.cond_br %tmp2, ^bb2(%a_1, %b_1, %i_0, %tmp1 : i32, i32, i32, i32), ^bb4(%a_1 : i32)
+  ^bb2(%a_2: i32, %b_2: i32, %i_1: i32, %tmp3: i32):
+    %tmp4 = arith.addi %a_2, %b_2 : i32
+    cf.br ^bb3(%b_2, %tmp4, %i_1, %tmp3 : i32, i32, i32, i32)
+  ^bb3(%a_3: i32, %b_3: i32, %i_2: i32, %tmp5: i32):
+    %tmp6 = arith.constant 1 : i32
+    %i_3 = arith.addi %i_2, %tmp6 : i32
+    cf.br ^bb1(%a_3, %b_3, %i_3, %tmp5 : i32, i32, i32, i32)
+  ^bb4(%a_4: i32):
+    call @print(%a_4) : (i32) -> ()
+    %tmp7 = arith.constant 0 : i32
+    return %tmp7 : i32
+  }
+}
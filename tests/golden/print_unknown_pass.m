@@ -0,0 +1,3 @@
+BEGIN
+  WRITE(1 + 2);
+END
@@ -0,0 +1,4 @@
+CONST N = 5, M = N * 2;
+BEGIN
+  WRITE(N, M);
+END
@@ -0,0 +1,7 @@
+BEGIN
+  DECLARE r : REAL;
+  READ(n);
+  r := n / 2 + 0.5;
+  r *= n;
+  WRITE(r, n * 1.5 > r);
+END
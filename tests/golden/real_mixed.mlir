@@ -0,0 +1,39 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %n_0 = call @read() : () -> i32
+    %tmp0 = arith.constant 2 : i32
+    %tmp1 = arith.divsi %n_0, %tmp0 : i32
+    %tmp2 = arith.sitofp %tmp1 : i32 to f64
+    %tmp3 = arith.constant 5.0e-1 : f64
+    %r_0 = arith.addf %tmp2, %tmp3 : f64
+    %tmp4 = arith.sitofp %n_0 : i32 to f64
+    %r_1 = arith.mulf %r_0, %tmp4 : f64
+    call @writereal(%r_1) : (f64) -> ()
+    %tmp5 = arith.sitofp %n_0 : i32 to f64
+    %tmp6 = arith.constant 1.5e0 : f64
+    %tmp7 = arith.mulf %tmp5, %tmp6 : f64
+    %tmp8 = arith.cmpf ogt, %tmp7, %r_1 : f64
+    call @print_bool(%tmp8) : (i1) -> ()
+    %tmp9 = arith.constant 0 : i32
+    return %tmp9 : i32
+  }
+}
@@ -0,0 +1,5 @@
+BEGIN
+  DECLARE r : REAL;
+  READ(r);
+  WRITE(r <> 0.5, NOT (r = 0.5));
+END
@@ -0,0 +1,34 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %r_0 = call @readreal() : () -> f64
+    %tmp0 = arith.constant 5.0e-1 : f64
+    %tmp1 = arith.cmpf une, %r_0, %tmp0 : f64
+    call @print_bool(%tmp1) : (i1) -> ()
+    %tmp2 = arith.constant 5.0e-1 : f64
+    %tmp3 = arith.cmpf oeq, %r_0, %tmp2 : f64
+    %tmp4 = arith.constant 1 : i1
+    %tmp5 = arith.xori %tmp3, %tmp4 : i1
+    call @print_bool(%tmp5) : (i1) -> ()
+    %tmp6 = arith.constant 0 : i32
+    return %tmp6 : i32
+  }
+}
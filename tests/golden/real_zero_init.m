@@ -0,0 +1,6 @@
+BEGIN
+  DECLARE r : REAL;
+  READ(n);
+  IF n > 0 THEN r := 1.5; ENDIF
+  WRITE(r);
+END
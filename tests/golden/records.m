@@ -0,0 +1,11 @@
+TYPE Point = RECORD x, y : INTEGER END;
+PROCEDURE move(p : Point)
+  p.x := p.x + 1;
+END
+BEGIN
+  DECLARE p : Point;
+  p.x := 1;
+  READ(p.y);
+  move(p);
+  WRITE(p.x, p.y);
+END
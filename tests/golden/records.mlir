@@ -0,0 +1,50 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func private @micro_move(%p_0: !llvm.ptr) {
+    %tmp0 = llvm.getelementptr %p_0[0, 0] : (!llvm.ptr) -> !llvm.ptr, !llvm.struct<(i32, i32)>
+    %tmp1 = llvm.load %tmp0 : !llvm.ptr -> i32
+    %tmp2 = arith.constant 1 : i32
+    %tmp3 = arith.addi %tmp1, %tmp2 : i32
+    %tmp4 = llvm.getelementptr %p_0[0, 0] : (!llvm.ptr) -> !llvm.ptr, !llvm.struct<(i32, i32)>
+    llvm.store %tmp3, %tmp4 : i32, !llvm.ptr
+    return
+  }
+
+  func.func @main() -> i32 {
+    %tmp0 = llvm.mlir.constant(1 : i64) : i64
+    %p_0 = llvm.alloca %tmp0 x !llvm.struct<(i32, i32)> : (i64) -> !llvm.ptr
+    %tmp1 = arith.constant 1 : i32
+    %tmp2 = llvm.getelementptr %p_0[0, 0] : (!llvm.ptr) -> !llvm.ptr, !llvm.struct<(i32, i32)>
+    llvm.store %tmp1, %tmp2 : i32, !llvm.ptr
+    %tmp3 = call @read() : () -> i32
+    %tmp4 = llvm.getelementptr %p_0[0, 1] : (!llvm.ptr) -> !llvm.ptr, !llvm.struct<(i32, i32)>
+    llvm.store %tmp3, %tmp4 : i32, !llvm.ptr
+    call @micro_move(%p_0) : (!llvm.ptr) -> ()
+    %tmp5 = llvm.getelementptr %p_0[0, 0] : (!llvm.ptr) -> !llvm.ptr, !llvm.struct<(i32, i32)>
+    %tmp6 = llvm.load %tmp5 : !llvm.ptr -> i32
+    call @print(%tmp6) : (i32) -> ()
+    %tmp7 = llvm.getelementptr %p_0[0, 1] : (!llvm.ptr) -> !llvm.ptr, !llvm.struct<(i32, i32)>
+    %tmp8 = llvm.load %tmp7 : !llvm.ptr -> i32
+    call @print(%tmp8) : (i32) -> ()
+    %tmp9 = arith.constant 0 : i32
+    return %tmp9 : i32
+  }
+}
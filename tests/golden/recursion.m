@@ -0,0 +1,11 @@
+FUNCTION fact(n)
+  IF n <= 1 THEN RETURN 1; ENDIF
+  RETURN n * fact(n - 1);
+END
+PROCEDURE show(x)
+  WRITE(x);
+END
+BEGIN
+  READ(n);
+  show(fact(n));
+END
@@ -0,0 +1,48 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func private @micro_fact(%n_0: i32) -> i32 {
+    %tmp0 = arith.constant 1 : i32
+    %tmp1 = arith.cmpi sle, %n_0, %tmp0 : i32
+    cf.cond_br %tmp1, ^bb1, ^bb2(%n_0 : i32)
+  ^bb1:
+    %tmp2 = arith.constant 1 : i32
+    return %tmp2 : i32
+  ^bb2(%n_1: i32):
+    %tmp3 = arith.constant 1 : i32
+    %tmp4 = arith.subi %n_1, %tmp3 : i32
+    %tmp5 = call @micro_fact(%tmp4) : (i32) -> i32
+    %tmp6 = arith.muli %n_1, %tmp5 : i32
+    return %tmp6 : i32
+  }
+
+  func.func private @micro_show(%x_0: i32) {
+    call @print(%x_0) : (i32) -> ()
+    return
+  }
+
+  func.func @main() -> i32 {
+    %n_0 = call @read() : () -> i32
+    %tmp0 = call @micro_fact(%n_0) : (i32) -> i32
+    call @micro_show(%tmp0) : (i32) -> ()
+    %tmp1 = arith.constant 0 : i32
+    return %tmp1 : i32
+  }
+}
@@ -0,0 +1,9 @@
+BEGIN
+  READ(n);
+  f := 1;
+  REPEAT
+    f := f * n;
+    n := n - 1;
+  UNTIL n <= 1;
+  WRITE(f);
+END
@@ -0,0 +1,39 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %n_0 = call @read() : () -> i32
+    %f_0 = arith.constant 1 : i32
+    cf.br ^bb1(%n_0, %f_0 : i32, i32)
+  ^bb1(%n_1: i32, %f_1: i32):
+    %f_2 = arith.muli %f_1, %n_1 : i32
+    %tmp0 = arith.constant 1 : i32
+    %n_2 = arith.subi %n_1, %tmp0 : i32
+    cf.br ^bb2(%n_2, %f_2 : i32, i32)
+  ^bb2(%n_3: i32, %f_3: i32):
+    %tmp1 = arith.constant 1 : i32
+    %tmp2 = arith.cmpi sle, %n_3, %tmp1 : i32
+    cf.cond_br %tmp2, ^bb3(%f_3 : i32), ^bb1(%n_3, %f_3 : i32, i32)
+  ^bb3(%f_4: i32):
+    call @print(%f_4) : (i32) -> ()
+    %tmp3 = arith.constant 0 : i32
+    return %tmp3 : i32
+  }
+}
@@ -0,0 +1,5 @@
+BEGIN
+  CONST C = 256 SHR 36;
+  READ(x, y);
+  WRITE(x SHL y, C);
+END
@@ -0,0 +1,33 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %x_0 = call @read() : () -> i32
+    %y_0 = call @read() : () -> i32
+    %tmp0 = arith.constant 31 : i32
+    %tmp1 = arith.andi %y_0, %tmp0 : i32
+    %tmp2 = arith.shli %x_0, %tmp1 : i32
+    call @print(%tmp2) : (i32) -> ()
+    %tmp3 = arith.constant 16 : i32
+    call @print(%tmp3) : (i32) -> ()
+    %tmp4 = arith.constant 0 : i32
+    return %tmp4 : i32
+  }
+}
@@ -0,0 +1,6 @@
+BEGIN
+  READ(x);
+  x := x + 1;
+  x := x * x;
+  WRITE(x);
+END
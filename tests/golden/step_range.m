@@ -0,0 +1,3 @@
+BEGIN
+  FOR i := 1 TO 10 STEP 3000000000 DO WRITE(i); ENDFOR
+END
@@ -0,0 +1,8 @@
+BEGIN
+  DECLARE n : INTEGER;
+  DECLARE r : REAL;
+  READ(n);
+  total := n + 1;
+  n := r;
+  WRITE(total);
+END
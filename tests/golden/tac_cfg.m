@@ -0,0 +1,9 @@
+BEGIN
+  READ(n);
+  s := 0;
+  WHILE n > 0 DO
+    IF n MOD 2 = 0 THEN s := s + n; ELSE s := s - 1; ENDIF
+    n := n - 1;
+  ENDWHILE
+  WRITE(s);
+END
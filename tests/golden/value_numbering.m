@@ -0,0 +1,4 @@
+BEGIN
+  READ(a, b);
+  WRITE((a + b) * 2, (a + b) * 2, a + 2);
+END
@@ -0,0 +1,10 @@
+BEGIN
+  READ(x);
+  i := 0;
+  s := 0;
+  WHILE i < x DO
+    i := i + 1;
+    s := s + i;
+  ENDWHILE
+  WRITE(i, s);
+END
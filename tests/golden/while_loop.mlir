@@ -0,0 +1,40 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+
+  func.func @main() -> i32 {
+    %x_0 = call @read() : () -> i32
+    %i_0 = arith.constant 0 : i32
+    %s_0 = arith.constant 0 : i32
+    cf.br ^bb1(%x_0, %i_0, %s_0 : i32, i32, i32)
+  ^bb1(%x_1: i32, %i_1: i32, %s_1: i32):
+    %tmp0 = arith.cmpi slt, %i_1, %x_1 : i32
+    cf.cond_br %tmp0, ^bb2(%x_1, %i_1, %s_1 : i32, i32, i32), ^bb3(%i_1, %s_1 : i32, i32)
+  ^bb2(%x_2: i32, %i_2: i32, %s_2: i32):
+    %tmp1 = arith.constant 1 : i32
+    %i_3 = arith.addi %i_2, %tmp1 : i32
+    %s_3 = arith.addi %s_2, %i_3 : i32
+    cf.br ^bb1(%x_2, %i_3, %s_3 : i32, i32, i32)
+  ^bb3(%i_4: i32, %s_4: i32):
+    call @print(%i_4) : (i32) -> ()
+    call @print(%s_4) : (i32) -> ()
+    %tmp2 = arith.constant 0 : i32
+    return %tmp2 : i32
+  }
+}
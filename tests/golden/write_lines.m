@@ -0,0 +1,7 @@
+BEGIN
+  READ(a, b);
+  WRITE("sum = ", a + b);
+  WRITE(a, b);
+  WRITE("done");
+  WRITE("a", "b");
+END
@@ -0,0 +1,48 @@
+module {
+  func.func private @read() -> i32
+  func.func private @read_long() -> i64
+  func.func private @readreal() -> f64
+  func.func private @print(i32)
+  func.func private @print_long(i64)
+  func.func private @writereal(f64)
+  func.func private @print_bool(i1)
+  func.func private @power(i32, i32) -> i32
+  func.func private @power_long(i64, i64) -> i64
+  func.func private @print_str(!llvm.ptr)
+  func.func private @check_bounds(i64, i64, i32)
+  func.func private @exit(i32)
+  func.func private @check_assert(i1, !llvm.ptr)
+  func.func private @isqrt(i32) -> i32
+  func.func private @isqrt_long(i64) -> i64
+  func.func private @random_int(i32) -> i32
+  func.func private @random_long(i64) -> i64
+  func.func private @random_seed(i64)
+  llvm.mlir.global internal constant @str0("sum = \00") : !llvm.array<7 x i8>
+  llvm.mlir.global internal constant @str1("done\00") : !llvm.array<5 x i8>
+  llvm.mlir.global internal constant @str2("\0A\00") : !llvm.array<2 x i8>
+  llvm.mlir.global internal constant @str3("a\00") : !llvm.array<2 x i8>
+  llvm.mlir.global internal constant @str4("b\00") : !llvm.array<2 x i8>
+
+  func.func @main() -> i32 {
+    %a_0 = call @read() : () -> i32
+    %b_0 = call @read() : () -> i32
+    %tmp0 = llvm.mlir.addressof @str0 : !llvm.ptr
+    call @print_str(%tmp0) : (!llvm.ptr) -> ()
+    %tmp1 = arith.addi %a_0, %b_0 : i32
+    call @print(%tmp1) : (i32) -> ()
+    call @print(%a_0) : (i32) -> ()
+    call @print(%b_0) : (i32) -> ()
+    %tmp2 = llvm.mlir.addressof @str1 : !llvm.ptr
+    call @print_str(%tmp2) : (!llvm.ptr) -> ()
+    %tmp3 = llvm.mlir.addressof @str2 : !llvm.ptr
+    call @print_str(%tmp3) : (!llvm.ptr) -> ()
+    %tmp4 = llvm.mlir.addressof @str3 : !llvm.ptr
+    call @print_str(%tmp4) : (!llvm.ptr) -> ()
+    %tmp5 = llvm.mlir.addressof @str4 : !llvm.ptr
+    call @print_str(%tmp5) : (!llvm.ptr) -> ()
+    %tmp6 = llvm.mlir.addressof @str2 : !llvm.ptr
+    call @print_str(%tmp6) : (!llvm.ptr) -> ()
+    %tmp7 = arith.constant 0 : i32
+    return %tmp7 : i32
+  }
+}